@@ -1,11 +1,14 @@
 use socigy::logging;
+use socigy::manifest::plugin;
 use socigy::ui::ui_component;
-use socigy::{exports::*, ui::events::UIEvent, ui::ui};
+use socigy::{exports::*, ui::events::PressEvent, ui::ui};
 
 use socigy::ui::bindings::register_component;
 use socigy::ui::components::UIComponent;
 use socigy::ui::elements::{PropStr, UIElement};
-use socigy::utils::crypto::random_v4_uuid;
+use socigy::ui::hooks::use_state;
+use socigy::ui::style::FontWeight;
+use socigy::uuid::uuid;
 use socigy::{
     callback, error, info,
     permissions::{
@@ -18,6 +21,28 @@ use crate::ui::components::CounterBuilder;
 
 /// This function is after the plugin API is initialized
 /// Here you can register all of your UI elements or events
+#[plugin(
+    permission(
+        name = "socigy.ui.components.replace",
+        description = "Required to change your profile page appearance",
+        link = "https://dev.socigy.com/docs/permissions/ui/components/replace",
+        required = true,
+        component_ids = [
+            "81fada10-0924-4f60-bfe8-51c0ac228297",
+            "f74be237-f439-4ef0-85a8-a773db41e8bd"
+        ]
+    ),
+    permission(
+        name = "socigy.ui.components.delete",
+        link = "https://dev.socigy.com/docs/permissions/ui/components/delete",
+        required = true,
+        component_ids = ["8f45bf15-eade-4a72-a1e6-2e03b99a2654"]
+    ),
+    window(
+        id = "a005b6ce-a66e-4c81-924d-3d206e574718",
+        description = "Window for showing news, blogs and etc."
+    )
+)]
 #[wasm_bindgen]
 pub fn main() {
     logging::log(logging::LogLevel::Info, "Get Declared Permissions");
@@ -48,8 +73,7 @@ pub fn main() {
         );
     }));
 
-    let component_id = random_v4_uuid();
-    register_component::<Page>(&component_id);
+    register_component::<Page>(&uuid!("81fada10-0924-4f60-bfe8-51c0ac228297"));
 }
 
 #[ui_component]
@@ -107,13 +131,34 @@ impl UIComponent for Page {
             })
             .collect();
 
-        let a = |_e: &UIEvent| {};
+        let press_count = use_state(|| 0u32);
+        let a = {
+            let press_count = press_count.clone();
+            move |_e: PressEvent| press_count.update(|count| *count += 1)
+        };
+        let reset = {
+            let press_count = press_count.clone();
+            // The press is not counted by the page
+            move |e: PressEvent| {
+                e.stop_propagation();
+                press_count.set(0);
+            }
+        };
+
+        let name = use_state(String::new);
+
+        let press_weight = if press_count.get() % 2 == 0 {
+            FontWeight::Regular
+        } else {
+            FontWeight::Bold
+        };
 
         ui! {
-            <View class_name="flex-1 flex b" on_layout={|e| {
+            <View class_name="flex-1 flex" on_layout={|e| {
                 info!("OnLayout event was fired on Page. Event: {:?}", e);
             }} on_press={a}>
                 <Text class_name="text-2xl font-inter-bold text-foreground">Your watchlist</Text>
+                <Text style={{ padding_vertical: 4, font_weight: press_weight }}>Pressed " "{press_count.get().to_string()}" times"</Text>
                 {
                     (|| {
                         if self.render_string {
@@ -128,6 +173,26 @@ impl UIComponent for Page {
                         }
                     })()
                 }
+                <TextInput
+                    class_name="text-foreground bg-level-1 rounded-md px-2"
+                    value={name.get()}
+                    placeholder="Your name"
+                    on_change_text={name.bind_text()}
+                    on_submit_editing={|e| {
+                        info!("Name submitted: {}", e.text);
+                        // Keeps the keyboard open
+                        e.prevent_default();
+                    }}
+                />
+                <Text class_name="text-level-5">Hello " "{name.get()}</Text>
+                <View class_name="flex-row gap-2">
+                    <Pressable on_press={|_e| info!("Liked, the press is counted by the page as well")}>
+                        <Text class_name="text-foreground">Like</Text>
+                    </Pressable>
+                    <Pressable on_press={reset}>
+                        <Text class_name="text-foreground">Reset</Text>
+                    </Pressable>
+                </View>
                 <Counter render_string={self.render_string} image_url={self.image_url.clone().unwrap()} content={self.content.clone().unwrap()} />
             </View>
         }
@@ -2,7 +2,8 @@ use socigy::{
     info,
     ui::{
         components::UIComponent,
-        elements::{PropStr, UIElement},
+        elements::{PropStr, ResizeMode, UIElement},
+        list::use_list,
         ui, ui_component,
     },
 };
@@ -30,6 +31,17 @@ impl UIComponent for Counter {
             })
             .collect::<Vec<Option<UIElement>>>();
 
+        let items = (1..=50).map(|i| format!("Item {}", i)).collect::<Vec<_>>();
+        let list = use_list(
+            items.len(),
+            |index| index.to_string(),
+            move |index| {
+                ui! {
+                    <Text class_name="text-foreground">{items[index].clone()}</Text>
+                }
+            },
+        );
+
         ui! {
             <>
                 <View>
@@ -40,9 +52,7 @@ impl UIComponent for Counter {
                         </Text>
                     </External>
 
-                    {
-                        self.image_url.clone()
-                    }
+                    <Image source={self.image_url.clone()} resize_mode={ResizeMode::Cover} class_name="w-12 h-12 rounded-full" />
 
                     {
                         format!(" - Ahoj - {}", class_name)
@@ -67,10 +77,14 @@ impl UIComponent for Counter {
                         }
                     }
 
-                    <Text class_name="text-foreground font-inter-regular">Ja jsem dalsi text</Text>
+                    <Text class_name="text-foreground font-inter-regular" number_of_lines={1}>Ja jsem dalsi text</Text>
                     <FlatList
-                        on_end_reached={move |_e| {
-
+                        data={list}
+                        on_scroll={|e| {
+                            info!("The list was scrolled to {}", e.content_offset.y);
+                        }}
+                        on_end_reached={move |e| {
+                            info!("The end of the list was reached: {:?}", e);
                         }}
                         class_name={class_name}
                     />
@@ -79,6 +93,11 @@ impl UIComponent for Counter {
         }
     }
 
+    /// The counter renders only static content, so the presses of the page do not render it again
+    fn should_render(&self, previous: &Self) -> bool {
+        self != previous
+    }
+
     fn mount(&mut self) {}
 
     fn unmount(&mut self) {}
@@ -1,8 +1,25 @@
 use components::profile_test::ProfileTest;
-use socigy::{exports::*, ui::bindings::register_component, uuid::uuid};
+use socigy::{
+    exports::*, manifest::plugin, ui::bindings::register_component, uuid::uuid,
+};
 
 mod components;
 
+#[plugin(
+    permission(
+        name = "socigy.ui.components.replace",
+        description = "We need to replace some components to make the new design work",
+        link = "https://dev.socigy.com/docs/permissions/ui/components/replace",
+        required = true,
+        component_ids = ["6396e8ae-6ff9-4676-93a9-f1fb8f140e8d"]
+    ),
+    permission(
+        name = "socigy.ui.components.delete",
+        description = "We remove some components that are not needed in the new design",
+        link = "https://dev.socigy.com/docs/permissions/ui/components/delete",
+        required = false
+    )
+)]
 #[wasm_bindgen]
 pub fn main() {
     register_component::<ProfileTest>(&uuid!("6396e8ae-6ff9-4676-93a9-f1fb8f140e8d"));
@@ -18,12 +18,18 @@ class SocigyLoggingApi {
   log(message) {
     SocigyLogging.log(this.id, message);
   }
+  record(record) {
+    SocigyLogging.record(this.id, record);
+  }
   error(message, showUI, uiDelay) {
     SocigyLogging.error(this.id, message, showUI, uiDelay);
   }
   fatal(message, uiDelay) {
     SocigyLogging.fatal(this.id, message, uiDelay);
   }
+  crash(report, uiDelay) {
+    SocigyLogging.crash(this.id, report, uiDelay);
+  }
 }
 class SocigyPermissionsApi {
   constructor(id, instance) {
@@ -90,9 +96,10 @@ class SocigyUtilsApi {
   }
 }
 class SocigyUiApi {
-  constructor(id) {
+  constructor(id, instance) {
     this.eventRegistry = {};
     this.id = id;
+    this.instance = instance;
     this.events = {
       invokeEvent(id, e) {
         this.eventRegistry[id](e);
@@ -108,9 +115,20 @@ class SocigyUiApi {
       },
     };
     this.render = {
-      processComponentRenderChanges(componentId, changes) {
-        apiLog(id, "Renderer sent changes for component", componentId, changes);
-        SocigyUI.onComponentChange(id, componentId, changes);
+      processComponentRenderChanges(componentId, changes, instance) {
+        apiLog(id, "Renderer sent changes for component", componentId, instance, changes);
+        SocigyUI.onComponentChange(id, componentId, changes, instance);
+      },
+      onComponentError(componentId, error, instance) {
+        apiLog(id, "Component failed to render", componentId, instance, error);
+        SocigyUI.onComponentError(id, componentId, JSON.parse(error), instance);
+      },
+      processListItemChanges(listId, key, changes) {
+        apiLog(id, "Renderer sent changes for list item", listId, key, changes);
+        SocigyUI.onListItemChange(id, listId, key, changes);
+      },
+      requestFrame() {
+        requestAnimationFrame((timestamp) => instance.onFrame(timestamp));
       },
       processAppRenderChanges(id, changes) {},
     };
@@ -130,7 +148,7 @@ class SocigyScopedImports {
     this.permissions = new SocigyPermissionsApi(id, instance);
     this.device = new SocigyDeviceApi(id);
     this.utils = new SocigyUtilsApi(id);
-    this.ui = new SocigyUiApi(id);
+    this.ui = new SocigyUiApi(id, instance);
   }
 }
 class PluginInstance {
@@ -140,12 +158,25 @@ class PluginInstance {
     this.id = id;
     this.imports = new SocigyScopedImports(id, this);
   }
+  loadManifest() {
+    this.config = JSON.parse(this.api.get_plugin_manifest());
+    return this.config;
+  }
+  // Returns true if the plugin prevented the default behavior of the event.
+  // Press events are invoked only for the pressed element, the plugin bubbles them to its parents.
+  // Other events, e.g. touches, are invoked for every listener React Native delivers them to
   invokeUiEvent(id, e) {
-    this.api.invoke_ui_event(id, e);
+    return this.api.invoke_ui_event(id, e);
   }
   removeEventListener(id) {
     this.imports.ui.events.removeEventListener(id);
   }
+  setLogLevel(level, target) {
+    this.api.set_log_level(level, target);
+  }
+  invokePermissionChange(states) {
+    this.api.invoke_permission_change(JSON.stringify(states));
+  }
   getScopedImports() {
     const imports = Object.assign({}, this.imports);
     const internal_plugin_id = this.id;
@@ -273,9 +304,37 @@ class PluginInstance {
       );
     }
   }
-  renderComponent(componentId, props) {
-    const result = this.api.render_component(componentId, props);
-    SocigyUI.onComponentRender(this.id, componentId, result, null);
+  renderComponent(componentId, props, instance) {
+    const result = this.api.render_component(componentId, props, instance);
+    SocigyUI.onComponentRender(this.id, componentId, result, null, instance);
+  }
+  retryComponent(componentId, instance) {
+    const result = this.api.retry_component(componentId, instance);
+    SocigyUI.onComponentRender(this.id, componentId, result, null, instance);
+  }
+  renderListItem(listId, index) {
+    return this.api.render_list_item(listId, index);
+  }
+  releaseListItem(listId, key) {
+    this.api.release_list_item(listId, key);
+  }
+  onFrame(timestamp) {
+    this.api.on_frame(timestamp);
+  }
+  mountComponent(componentId, instance) {
+    this.api.mount_component(componentId, instance);
+  }
+  unmountComponent(componentId, instance) {
+    this.api.unmount_component(componentId, instance);
+  }
+  releaseComponentInstance(componentId, instance) {
+    this.api.release_component_instance(componentId, instance);
+  }
+  setComponentVisibility(componentId, visible, instance) {
+    this.api.set_component_visibility(componentId, visible, instance);
+  }
+  setAppState(state) {
+    this.api.set_app_state(state);
   }
 }
 globalThis.Socigy = { Instance: PluginInstance, loaded: {} };
@@ -16,6 +16,31 @@ interface PermissionState {
   canAskAgain: boolean;
 }
 
+interface LogRecord {
+  level: "DEBUG" | "INFO" | "WARN" | "ERROR" | "FATAL";
+  target: string;
+  message: string;
+  fields?: { [key: string]: any };
+  componentId?: string;
+  timestamp: number;
+}
+
+interface CrashReport {
+  message: string;
+  location?: { file: string; line: number; column: number };
+  pluginName?: string;
+  pluginVersion?: string;
+  componentId?: string;
+  breadcrumbs: LogRecord[];
+  context: { [key: string]: any };
+  timestamp: number;
+}
+
+// Panics are not render errors, they crash the plugin through socigy.logging.crash
+type RenderError =
+  | { type: "invalidProps"; message: string }
+  | { type: "empty" };
+
 interface PluginComponentPermission extends PluginPermission {
   componentIds: Guid[];
 }
@@ -25,9 +50,13 @@ interface PluginConfig {
   version: `${string}.${string}.${string}`;
   authors: string[];
   description?: string;
-  permissions: { [name: string]: PluginPermission };
+  language?: string;
+  permissions: { [name: string]: PluginPermission | PluginComponentPermission };
 
-  ui: {};
+  ui: {
+    components?: Guid[];
+    windows?: { [id: Guid]: string };
+  };
 }
 
 function apiLog(id: string, ...rest) {
@@ -53,8 +82,10 @@ declare global {
   // Id is the plugin_id
   var SocigyLogging: {
     log(id: string, message: string);
+    record(id: string, record: string);
     error(id: string, message: string, showUI: boolean, uiDelay: number);
     fatal(id: string, message: string, uiDelay: number);
+    crash(id: string, report: CrashReport, uiDelay: number);
   };
 
   var SocigyPermissions: {
@@ -74,12 +105,30 @@ declare global {
   var SocigyDevice: {};
 
   var SocigyUI: {
-    onComponentChange(pluginId: string, id: string, changes: string);
+    onComponentChange(
+      pluginId: string,
+      id: string,
+      changes: string,
+      instance: number
+    );
+    onComponentError(
+      pluginId: string,
+      id: string,
+      error: RenderError,
+      instance: number
+    );
+    onListItemChange(
+      pluginId: string,
+      listId: string,
+      key: string,
+      changes: string
+    );
     onComponentRender(
       pluginId: string,
       id: string,
       result?: string,
-      error?: string
+      error?: string,
+      instance?: number
     );
     removeComponent(pluginId: string, id: string);
     registerComponent(pluginId: string, id: string);
@@ -112,12 +161,18 @@ class SocigyLoggingApi {
   log(message: string) {
     SocigyLogging.log(this.id, message);
   }
+  record(record: string) {
+    SocigyLogging.record(this.id, record);
+  }
   error(message: string, showUI: boolean, uiDelay: number) {
     SocigyLogging.error(this.id, message, showUI, uiDelay);
   }
   fatal(message: string, uiDelay: number) {
     SocigyLogging.fatal(this.id, message, uiDelay);
   }
+  crash(report: CrashReport, uiDelay: number) {
+    SocigyLogging.crash(this.id, report, uiDelay);
+  }
 }
 
 class SocigyPermissionsApi {
@@ -176,12 +231,14 @@ class SocigyUtilsApi {
 
 class SocigyUiApi {
   private id: string;
+  private instance: PluginInstance;
   private eventRegistry: { [id: string]: (e: UIEvent) => void } = {};
   events: any;
   render: any;
 
-  constructor(id) {
+  constructor(id, instance) {
     this.id = id;
+    this.instance = instance;
     this.events = {
       invokeEvent(id: string, e: UIEvent) {
         this.eventRegistry[id](e);
@@ -197,9 +254,24 @@ class SocigyUiApi {
       },
     };
     this.render = {
-      processComponentRenderChanges(componentId: string, changes: string) {
-        apiLog(id, "Renderer sent changes for component", componentId, changes);
-        SocigyUI.onComponentChange(id, componentId, changes);
+      processComponentRenderChanges(
+        componentId: string,
+        changes: string,
+        instance: number
+      ) {
+        apiLog(id, "Renderer sent changes for component", componentId, instance, changes);
+        SocigyUI.onComponentChange(id, componentId, changes, instance);
+      },
+      onComponentError(componentId: string, error: string, instance: number) {
+        apiLog(id, "Component failed to render", componentId, instance, error);
+        SocigyUI.onComponentError(id, componentId, JSON.parse(error), instance);
+      },
+      processListItemChanges(listId: string, key: string, changes: string) {
+        apiLog(id, "Renderer sent changes for list item", listId, key, changes);
+        SocigyUI.onListItemChange(id, listId, key, changes);
+      },
+      requestFrame() {
+        requestAnimationFrame((timestamp) => instance.onFrame(timestamp));
       },
       processAppRenderChanges(id: string, changes: string) {},
     };
@@ -237,7 +309,7 @@ class SocigyScopedImports {
     this.permissions = new SocigyPermissionsApi(id, instance);
     this.device = new SocigyDeviceApi(id);
     this.utils = new SocigyUtilsApi(id);
-    this.ui = new SocigyUiApi(id);
+    this.ui = new SocigyUiApi(id, instance);
   }
 }
 
@@ -256,12 +328,26 @@ class PluginInstance {
     this.imports = new SocigyScopedImports(id, this);
   }
 
-  invokeUiEvent(id: string, e: string) {
-    this.api.invoke_ui_event(id, e);
+  loadManifest(): PluginConfig {
+    this.config = JSON.parse(this.api.get_plugin_manifest());
+    return this.config;
+  }
+
+  // Returns true if the plugin prevented the default behavior of the event.
+  // Press events are invoked only for the pressed element, the plugin bubbles them to its parents.
+  // Other events, e.g. touches, are invoked for every listener React Native delivers them to
+  invokeUiEvent(id: string, e: string): boolean {
+    return this.api.invoke_ui_event(id, e);
   }
   removeEventListener(id: string) {
     this.imports.ui.events.removeEventListener(id);
   }
+  setLogLevel(level: "debug" | "info" | "warn" | "error" | "fatal", target?: string) {
+    this.api.set_log_level(level, target);
+  }
+  invokePermissionChange(states: PermissionState | PermissionState[]) {
+    this.api.invoke_permission_change(JSON.stringify(states));
+  }
 
   private getScopedImports() {
     const imports = {
@@ -406,9 +492,41 @@ class PluginInstance {
     }
   }
 
-  renderComponent(componentId: string, props?: string) {
-    const result = this.api.render_component(componentId, props);
-    SocigyUI.onComponentRender(this.id, componentId, result, null);
+  renderComponent(componentId: string, props?: string, instance?: number) {
+    const result = this.api.render_component(componentId, props, instance);
+    SocigyUI.onComponentRender(this.id, componentId, result, null, instance);
+  }
+  retryComponent(componentId: string, instance?: number) {
+    const result = this.api.retry_component(componentId, instance);
+    SocigyUI.onComponentRender(this.id, componentId, result, null, instance);
+  }
+  renderListItem(listId: string, index: number): string | undefined {
+    return this.api.render_list_item(listId, index);
+  }
+  releaseListItem(listId: string, key: string) {
+    this.api.release_list_item(listId, key);
+  }
+  onFrame(timestamp: number) {
+    this.api.on_frame(timestamp);
+  }
+  mountComponent(componentId: string, instance?: number) {
+    this.api.mount_component(componentId, instance);
+  }
+  unmountComponent(componentId: string, instance?: number) {
+    this.api.unmount_component(componentId, instance);
+  }
+  releaseComponentInstance(componentId: string, instance: number) {
+    this.api.release_component_instance(componentId, instance);
+  }
+  setComponentVisibility(
+    componentId: string,
+    visible: boolean,
+    instance?: number
+  ) {
+    this.api.set_component_visibility(componentId, visible, instance);
+  }
+  setAppState(state: "foreground" | "background") {
+    this.api.set_app_state(state);
   }
 }
 
@@ -0,0 +1,58 @@
+// Exports the tokens of the app Tailwind theme into `socigy/macros/tailwind-theme.json`,
+// which the `ui!` macro validates the literal class names against.
+// Run `npm run export-tailwind-theme` once the dependencies of the app are installed
+import fs from "node:fs";
+import Module, { createRequire } from "node:module";
+import path from "node:path";
+import { fileURLToPath } from "node:url";
+const scriptsDir = path.dirname(fileURLToPath(import.meta.url));
+const appDir = path.resolve(scriptsDir, "../../client/native/app");
+const outputPath = path.resolve(scriptsDir, "../socigy/macros/tailwind-theme.json");
+// Tailwind is loaded from the app, so the theme is resolved with the same version and presets
+const appRequire = createRequire(path.join(appDir, "package.json"));
+// The config imports `react-native`, which can not be loaded by Node. It uses only `Platform`
+const load = Module._load;
+Module._load = function (request, ...rest) {
+    if (request === "react-native") {
+        return {
+            Platform: {
+                OS: "native",
+                select: (options) => { var _a; return (_a = options.native) !== null && _a !== void 0 ? _a : options.default; },
+            },
+        };
+    }
+    return load.call(this, request, ...rest);
+};
+const loadConfig = appRequire("tailwindcss/loadConfig");
+const resolveConfig = appRequire("tailwindcss/resolveConfig");
+const { theme } = resolveConfig(loadConfig(path.join(appDir, "tailwind.config.js")));
+/// Values of a theme scale. `DEFAULT` is used by the utility without a value, e.g. `rounded`
+function scale(values) {
+    return Object.keys(values !== null && values !== void 0 ? values : {}).filter((key) => key !== "DEFAULT");
+}
+const colors = [];
+const colorPalettes = [];
+const colorShades = new Set();
+for (const [name, value] of Object.entries(theme.colors)) {
+    if (typeof value === "string") {
+        colors.push(name);
+        continue;
+    }
+    colorPalettes.push(name);
+    if ("DEFAULT" in value)
+        colors.push(name);
+    scale(value).forEach((shade) => colorShades.add(shade));
+}
+const tokens = {
+    colors,
+    colorPalettes,
+    colorShades: [...colorShades],
+    fontFamily: scale(theme.fontFamily),
+    fontWeight: scale(theme.fontWeight),
+    fontSize: scale(theme.fontSize),
+    spacing: scale(theme.spacing),
+    borderRadius: scale(theme.borderRadius),
+    borderWidth: scale(theme.borderWidth),
+};
+fs.writeFileSync(outputPath, JSON.stringify(tokens, null, 2) + "\n");
+console.log(`Exported the Tailwind theme to ${outputPath}`);
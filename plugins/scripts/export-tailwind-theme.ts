@@ -0,0 +1,66 @@
+// Exports the tokens of the app Tailwind theme into `socigy/macros/tailwind-theme.json`,
+// which the `ui!` macro validates the literal class names against.
+// Run `npm run export-tailwind-theme` once the dependencies of the app are installed
+import fs from "node:fs";
+import Module, { createRequire } from "node:module";
+import path from "node:path";
+import { fileURLToPath } from "node:url";
+
+const scriptsDir = path.dirname(fileURLToPath(import.meta.url));
+const appDir = path.resolve(scriptsDir, "../../client/native/app");
+const outputPath = path.resolve(scriptsDir, "../socigy/macros/tailwind-theme.json");
+
+// Tailwind is loaded from the app, so the theme is resolved with the same version and presets
+const appRequire = createRequire(path.join(appDir, "package.json"));
+
+// The config imports `react-native`, which can not be loaded by Node. It uses only `Platform`
+const load = (Module as any)._load;
+(Module as any)._load = function (request: string, ...rest: any[]) {
+  if (request === "react-native") {
+    return {
+      Platform: {
+        OS: "native",
+        select: (options: any) => options.native ?? options.default,
+      },
+    };
+  }
+  return load.call(this, request, ...rest);
+};
+
+const loadConfig = appRequire("tailwindcss/loadConfig");
+const resolveConfig = appRequire("tailwindcss/resolveConfig");
+const { theme } = resolveConfig(loadConfig(path.join(appDir, "tailwind.config.js")));
+
+/// Values of a theme scale. `DEFAULT` is used by the utility without a value, e.g. `rounded`
+function scale(values: { [key: string]: any } | undefined): string[] {
+  return Object.keys(values ?? {}).filter((key) => key !== "DEFAULT");
+}
+
+const colors: string[] = [];
+const colorPalettes: string[] = [];
+const colorShades = new Set<string>();
+for (const [name, value] of Object.entries<any>(theme.colors)) {
+  if (typeof value === "string") {
+    colors.push(name);
+    continue;
+  }
+
+  colorPalettes.push(name);
+  if ("DEFAULT" in value) colors.push(name);
+  scale(value).forEach((shade) => colorShades.add(shade));
+}
+
+const tokens = {
+  colors,
+  colorPalettes,
+  colorShades: [...colorShades],
+  fontFamily: scale(theme.fontFamily),
+  fontWeight: scale(theme.fontWeight),
+  fontSize: scale(theme.fontSize),
+  spacing: scale(theme.spacing),
+  borderRadius: scale(theme.borderRadius),
+  borderWidth: scale(theme.borderWidth),
+};
+
+fs.writeFileSync(outputPath, JSON.stringify(tokens, null, 2) + "\n");
+console.log(`Exported the Tailwind theme to ${outputPath}`);
@@ -1,18 +1,19 @@
-import PluginConfig from '../../example-plugin/plugin.json' assert { type: "json" };
-export { PluginConfig };
 export class Permissions {
+    constructor(config) {
+        this.config = config;
+    }
     getPermissions() {
-        return Object.keys(PluginConfig.permissions).map((permissionKey) => {
+        return Object.keys(this.config.permissions).map((permissionKey) => {
             return {
                 name: permissionKey,
-                granted: PluginConfig.permissions[permissionKey].isRequired ? true : false,
+                granted: this.config.permissions[permissionKey].required ? true : false,
                 canAskAgain: true
             };
         });
     }
     getDeclaredPermissions() {
-        return Object.keys(PluginConfig.permissions).map((permissionKey) => {
-            return Object.assign(Object.assign({}, PluginConfig.permissions[permissionKey]), { name: permissionKey });
+        return Object.keys(this.config.permissions).map((permissionKey) => {
+            return Object.assign(Object.assign({}, this.config.permissions[permissionKey]), { name: permissionKey });
         });
     }
     requestPermissions(permissions) {
@@ -1,22 +1,28 @@
-import PluginConfig from '../../example-plugin/plugin.json' assert { type: "json" };
-
-export { PluginConfig }
+export interface PluginManifest {
+    permissions: { [name: string]: { description: string, link?: string, required: boolean } }
+}
 
 export class Permissions {
+    private config: PluginManifest;
+
+    constructor(config: PluginManifest) {
+        this.config = config;
+    }
+
     getPermissions(): PermissionState[] {
-        return Object.keys(PluginConfig.permissions).map((permissionKey) => {
+        return Object.keys(this.config.permissions).map((permissionKey) => {
             return {
                 name: permissionKey,
-                granted: PluginConfig.permissions[permissionKey].isRequired ? true : false,
+                granted: this.config.permissions[permissionKey].required ? true : false,
                 canAskAgain: true
             }
         });
     }
 
     getDeclaredPermissions(): PermissionDeclaration[] {
-        return Object.keys(PluginConfig.permissions).map((permissionKey) => {
+        return Object.keys(this.config.permissions).map((permissionKey) => {
             return {
-                ...PluginConfig.permissions[permissionKey],
+                ...this.config.permissions[permissionKey],
                 name: permissionKey
             }
         })
@@ -34,11 +40,11 @@ export class Permissions {
 export interface PermissionDeclaration {
     name: string,
     description: string,
-    isRequired: boolean
+    required: boolean
 }
 
 export interface PermissionState {
     name: string,
     granted: boolean,
     canAskAgain: boolean
-}
\ No newline at end of file
+}
@@ -1,8 +1,23 @@
 import * as TestPlugin from "../example-plugin/pkg/example_plugin.js";
+import crypto from "node:crypto";
 import fs from "node:fs";
 import { Permissions as PermissionsMock } from "./mock/permissions.js";
 const compiled = await WebAssembly.compile(fs.readFileSync("../example-plugin/pkg/example_plugin_bg.wasm"));
 let output = null;
+function formatRecord(json) {
+    const record = JSON.parse(json);
+    const date = new Date(record.timestamp);
+    const time = `${date.getHours().toString().padStart(2, "0")}:${date
+        .getMinutes()
+        .toString()
+        .padStart(2, "0")}:${date.getSeconds().toString().padStart(2, "0")}:${date
+        .getMilliseconds()
+        .toString()
+        .padStart(3, "0")}`;
+    const component = record.componentId ? ` [${record.componentId}]` : "";
+    const fields = record.fields ? ` ${JSON.stringify(record.fields)}` : "";
+    return `${time} ${record.level.padStart(5)}: ${record.target}${component} => ${record.message}${fields}`;
+}
 function colorizeLogs(message) {
     let index = message.indexOf("INFO");
     if (index !== -1) {
@@ -36,7 +51,7 @@ function colorizeLogs(message) {
     }
     return message;
 }
-const permissions = new PermissionsMock();
+let permissions = null;
 function apiLog(...rest) {
     const date = new Date();
     const hours = date.getHours().toString().padStart(2, "0");
@@ -50,61 +65,14 @@ let results = 0;
 let events = {};
 let components = new Set();
 let onChange = null;
-const uuidArray = [
-    "081c6297-ae56-41d9-afbe-c398dd5173d1",
-    "479ac2a8-68ae-4588-aa0f-ec20b8070120",
-    "9497272d-7af4-4e14-90ee-db9795c0e2bd",
-    "2cf14530-5fc6-4008-93f3-c38b7f2bbf06",
-    "e6c0580a-b75b-4fec-8960-e7a605550c0c",
-    "0d893eeb-da6b-4743-b1ab-1859beda9524",
-    "d2a01f3f-a6c8-4a1f-bf9c-308195ee50e7",
-    "1b65859a-07f3-4748-9007-d498a1e17ed5",
-    "6307193d-7803-4718-b2a2-5ff5e8064f9e",
-    "ebe48aeb-1b62-4203-92b9-964d2abd34d9",
-    "da283db1-15b8-450b-90a3-c289d1c2c715",
-    "f2c68262-0f30-495b-af9a-ef9b68c08188",
-    "7f2defe8-917c-4e05-8971-b8aa1f94fde8",
-    "8f305cd3-3d44-4c84-9d96-28885fa1b9a5",
-    "b33d6cf2-8d72-4b05-b07b-883fc2d20b5d",
-    "3f9a47b4-889a-47e6-b8fb-dea0573587b1",
-    "515434a8-79a3-469e-9291-d090d790c5ae",
-    "db6c0df5-b726-4d61-bf0a-85bfa5e9f7a7",
-    "1ac7de68-5966-4aca-b74a-81f3c465a67c",
-    "1ec71b5d-0fcf-40df-911e-4fbe46a6ac18",
-    "08729b70-afd6-4cd8-ad55-8723a476169a",
-    "f7841aa5-39da-4e0a-a7da-ca6eb296d45b",
-    "d97ed542-2d73-4226-8f29-719a61873806",
-    "ec48eb71-5cb4-472f-8f0d-c1ce373a9a56",
-    "d4baa518-7d04-4649-99ff-e4e7f19a5d95",
-    "00ccf5da-e41a-481c-a5b4-2596550b5944",
-    "9588ca80-bff4-4889-be93-908df9a63c22",
-    "58471817-a579-4dbb-beb6-9eb532bce856",
-    "c968c263-7655-485d-a07b-1ccf08d4a5ae",
-    "3809d31d-b20b-4ddb-bc3e-547372bdd213",
-    "b6385bf1-f6e6-49be-ae1d-c21e7e3505ab",
-    "8b3dac80-cdc4-44db-ad18-b89e35914b75",
-    "9b986697-85d6-4131-9f28-e87adbca9e56",
-    "dc1af522-fc76-421d-b4c9-3ce3a6e7c27f",
-    "3e27df9a-e978-4085-b22e-fc37e4b9868b",
-    "8d12762b-0afe-4187-8822-e01d649e0db7",
-    "45137fb2-8996-4628-a899-88b5ccc85184",
-    "6915c069-9d34-41b5-994c-fa417d2d100f",
-    "18cbf20f-9f05-4996-8d02-d92446a8a320",
-    "f1483dc9-3171-45c7-8fe9-a2cd7aa09661",
-    "151a272d-ff83-4e7e-b7c5-edcaa580679e",
-    "dd1e7088-439b-4ae9-9d5e-5247673d18a6",
-    "149a58f5-9d01-4548-ac45-45983475f532",
-    "d1c59015-f5b1-4af3-a64f-b1bd6a018082",
-    "acb5b1ca-99b8-4a56-8254-4d0ee40ed8ad",
-    "f2e946ec-a5d8-45e1-956b-69044350846f",
-    "e5bcd93e-3932-4f9d-a807-34443d400b3a",
-    "84af71d4-d172-4beb-a45e-1e7fca93617e",
-    "7706e791-631b-4a27-a7c3-bc4717136261",
-    "489249ff-5970-4639-80a9-a728dc44539b",
-];
+/// Last changes sent for every instance
+let instanceChanges = {};
+/// Whether the plugin waits for the next frame. The checks render it with `renderFrame`
+let frameRequested = false;
 globalThis.socigy = {
     logging: {
         log: (message) => console.log(colorizeLogs(message)),
+        record: (record) => console.log(colorizeLogs(formatRecord(record))),
         error: (message, showUi, uiDelay) => {
             console.error(colorizeLogs(message));
         },
@@ -112,6 +80,23 @@ globalThis.socigy = {
             console.error(`\x1b[31mAPI-FATAL:\x1b[0m Plugin has exited with: ${message}`);
             output = null;
         },
+        crash: (report, uiDelay) => {
+            const location = report.location
+                ? ` at ${report.location.file}:${report.location.line}:${report.location.column}`
+                : "";
+            console.error(`\x1b[31mAPI-CRASH:\x1b[0m Plugin ${report.pluginName}@${report.pluginVersion} has panicked${location}: ${report.message}`);
+            if (report.componentId) {
+                console.error(`  while rendering component ${report.componentId}`);
+            }
+            if (Object.keys(report.context).length > 0) {
+                console.error(`  context: ${JSON.stringify(report.context)}`);
+            }
+            console.error(`  last ${report.breadcrumbs.length} log records:`);
+            for (const record of report.breadcrumbs) {
+                console.error(`    ${colorizeLogs(formatRecord(JSON.stringify(record)))}`);
+            }
+            output = null;
+        },
     },
     permissions: {
         getDeclaredPermissions: (callbackId) => {
@@ -129,8 +114,7 @@ globalThis.socigy = {
     utils: {
         crypto: {
             randomV4Uuid() {
-                const randomIndex = Math.floor(Math.random() * uuidArray.length);
-                return uuidArray[randomIndex];
+                return crypto.randomUUID();
             },
         },
     },
@@ -146,10 +130,24 @@ globalThis.socigy = {
             },
         },
         render: {
-            processComponentRenderChanges(id, changes_str) {
+            processComponentRenderChanges(id, changes_str, instance) {
                 let changes = JSON.parse(changes_str);
-                apiLog("Renderer sent changes for component", id, changes);
-                onChange(changes);
+                apiLog("Renderer sent changes for component", id, instance, changes);
+                instanceChanges[instance] = changes;
+                // The mock keeps the VDOM of the default instance only
+                if (instance === 0)
+                    onChange(changes);
+            },
+            onComponentError(id, error_str, instance) {
+                var _a;
+                const error = JSON.parse(error_str);
+                console.error(`\x1b[31mAPI-RENDER-ERROR:\x1b[0m Component ${id}#${instance} failed to render (${error.type})`, (_a = error.message) !== null && _a !== void 0 ? _a : "");
+            },
+            processListItemChanges(listId, key, changes_str) {
+                apiLog("Renderer sent changes for list item", listId, key, JSON.parse(changes_str));
+            },
+            requestFrame() {
+                frameRequested = true;
             },
             processAppRenderChanges(id, changes_str) { },
         },
@@ -168,8 +166,12 @@ globalThis.socigy = {
     },
 };
 output = TestPlugin.initSync({ module: compiled });
+permissions = new PermissionsMock(JSON.parse(TestPlugin.get_plugin_manifest()));
 async function initializePlugin() {
     TestPlugin.initialize();
+    if (process.env.SOCIGY_LOG_LEVEL) {
+        TestPlugin.set_log_level(process.env.SOCIGY_LOG_LEVEL);
+    }
     apiLog("Socigy implementation initialized");
     TestPlugin.main();
     apiLog("User plugin initialized");
@@ -202,6 +204,42 @@ async function v3() {
                 case "updateChild":
                     result.children[x.path[x.path.length - 1]] = x.child;
                     break;
+                case "addElement":
+                    result.children.splice(x.path[x.path.length - 1], 0, x.element);
+                    break;
+                case "removeElement":
+                    result.children.splice(x.path[x.path.length - 1], 1);
+                    break;
+                case "moveElement":
+                    const [moved] = result.children.splice(x.path[x.path.length - 1], 1);
+                    result.children.splice(x.to, 0, moved);
+                    break;
+                case "addEventListener": {
+                    var _b;
+                    const element = x.path.length > 1 ? result.children[x.path[x.path.length - 1]] : result;
+                    element.events = (_b = element.events) !== null && _b !== void 0 ? _b : {};
+                    element.events[x.name] = [...((_b = element.events[x.name]) !== null && _b !== void 0 ? _b : []), x.id];
+                    break;
+                }
+                case "removeEventListener": {
+                    const element = x.path.length > 1 ? result.children[x.path[x.path.length - 1]] : result;
+                    element.events[x.name] = element.events[x.name].filter((id) => id !== x.id);
+                    break;
+                }
+                case "updateStyle": {
+                    var _c;
+                    const element = x.path.length > 1 ? result.children[x.path[x.path.length - 1]] : result;
+                    element.props = (_c = element.props) !== null && _c !== void 0 ? _c : {};
+                    const style = Object.assign({}, element.props.style);
+                    for (const [key, value] of Object.entries(x.styles)) {
+                        if (value === null)
+                            delete style[key];
+                        else
+                            style[key] = value;
+                    }
+                    element.props.style = style;
+                    break;
+                }
             }
         });
         apiLog("Updated VDOM", vdom);
@@ -217,5 +255,136 @@ async function v3() {
     TestPlugin.render_component(components.values().next().value, '{"renderString": false,"content":"Nazdar","imageUrl":"https://socigy.com/favicon/favicon.svg"}');
     apiLog("2nd render VDOM:", JSON.stringify(vdom));
 }
+/// Props of the example component rendered by the checks
+function componentProps(renderString) {
+    return JSON.stringify({
+        renderString,
+        content: "Nazdar",
+        imageUrl: "https://socigy.com/favicon/favicon.svg",
+    });
+}
+/// Returns the check of a suite. Failed checks are reported and fail the whole run
+function checks(suite) {
+    return (condition, message) => {
+        if (condition) {
+            apiLog(`${suite} check passed:`, message);
+        }
+        else {
+            console.error(`\x1b[31m${suite.toUpperCase().replace(/ /g, "-")}-CHECK-FAILED:\x1b[0m ${message}`);
+            process.exitCode = 1;
+        }
+    };
+}
+/// Returns the elements of the type in the rendered UI, in the order they are rendered
+function findElements(element, type, found = []) {
+    var _a;
+    if ((element === null || element === void 0 ? void 0 : element.type) === type)
+        found.push(element);
+    for (const child of (_a = element === null || element === void 0 ? void 0 : element.children) !== null && _a !== void 0 ? _a : []) {
+        findElements(child, type, found);
+    }
+    return found;
+}
+function findElement(element, type) {
+    return findElements(element, type)[0];
+}
+/// Renders the frame requested by the plugin, as the host does on the next animation frame
+function renderFrame() {
+    if (!frameRequested)
+        return;
+    frameRequested = false;
+    TestPlugin.on_frame(performance.now());
+}
+async function lifecycle() {
+    const componentId = components.values().next().value;
+    const props = componentProps(true);
+    const expect = checks("Lifecycle");
+    TestPlugin.mount_component(componentId);
+    TestPlugin.set_component_visibility(componentId, false);
+    TestPlugin.set_component_visibility(componentId, true);
+    TestPlugin.set_app_state("background");
+    TestPlugin.set_app_state("foreground");
+    TestPlugin.unmount_component(componentId);
+    expect(TestPlugin.render_component(componentId, props) !== undefined, "render after unmount returns the whole UI");
+    TestPlugin.mount_component(componentId);
+    expect(TestPlugin.render_component(componentId, props) === undefined, "render of a mounted component sends only the changes");
+    TestPlugin.unmount_component(componentId);
+}
+async function instances() {
+    const componentId = components.values().next().value;
+    const props = componentProps(false);
+    const expect = checks("Instance");
+    expect(TestPlugin.render_component(componentId, props) !== undefined, "first render of the default instance returns the whole UI");
+    expect(TestPlugin.render_component(componentId, props, 1) !== undefined, "first render of another instance returns the whole UI");
+    expect(TestPlugin.render_component(componentId, props, 1) === undefined, "instances keep their own VDOM");
+    TestPlugin.release_component_instance(componentId, 1);
+    expect(TestPlugin.render_component(componentId, props, 1) !== undefined, "released instance starts from scratch");
+    expect(TestPlugin.render_component(componentId, props) === undefined, "releasing an instance keeps the other instances");
+    TestPlugin.release_component_instance(componentId, 1);
+}
+async function textInput() {
+    var _a, _b, _c;
+    const componentId = components.values().next().value;
+    const props = componentProps(false);
+    const expect = checks("Text input");
+    const ui = JSON.parse(TestPlugin.render_component(componentId, props, 2));
+    const input = findElement(ui, "TextInput");
+    expect(((_b = (_a = input === null || input === void 0 ? void 0 : input.events) === null || _a === void 0 ? void 0 : _a.onChangeText) === null || _b === void 0 ? void 0 : _b.length) === 1, "text input registers its onChangeText listener");
+    TestPlugin.invoke_ui_event(input.events.onChangeText[0], JSON.stringify({ type: "onChangeText", text: "Ada" }));
+    // The state change is rendered on the next frame
+    renderFrame();
+    expect(((_c = instanceChanges[2]) !== null && _c !== void 0 ? _c : []).some((change) => change.type === "updateProp" && change.key === "value" && change.value === "Ada"), "typing updates the bound value");
+    TestPlugin.release_component_instance(componentId, 2);
+}
+async function lists() {
+    var _a, _b, _c;
+    const componentId = components.values().next().value;
+    const props = componentProps(false);
+    const expect = checks("List");
+    const ui = JSON.parse(TestPlugin.render_component(componentId, props, 3));
+    const list = findElement(ui, "FlatList");
+    const data = (_a = list === null || list === void 0 ? void 0 : list.props) === null || _a === void 0 ? void 0 : _a.data;
+    expect(((_b = data === null || data === void 0 ? void 0 : data.keys) === null || _b === void 0 ? void 0 : _b.length) === 50, "list sends the keys of all items");
+    expect(findElement(list, "Text") === undefined, "list does not render its items upfront");
+    const item = JSON.parse((_c = TestPlugin.render_list_item(data.id, 1)) !== null && _c !== void 0 ? _c : "null");
+    expect((item === null || item === void 0 ? void 0 : item.key) === "1", "item is rendered with its key");
+    expect(TestPlugin.render_list_item(data.id, 50) === undefined, "item out of the list is not rendered");
+    TestPlugin.release_list_item(data.id, "1");
+    TestPlugin.release_component_instance(componentId, 3);
+    expect(TestPlugin.render_list_item(data.id, 1) === undefined, "list is removed with its component");
+}
+async function propagation() {
+    const componentId = components.values().next().value;
+    const props = componentProps(false);
+    const expect = checks("Propagation");
+    const press = JSON.stringify({
+        type: "onPress",
+        changedTouches: [],
+        identifier: "0",
+        locationX: 0,
+        locationY: 0,
+        pageX: 0,
+        pageY: 0,
+        target: "0",
+        touches: [],
+    });
+    const pageCounted = () => { var _a; return ((_a = instanceChanges[4]) !== null && _a !== void 0 ? _a : []).some((change) => change.type === "updateStyle"); };
+    const ui = JSON.parse(TestPlugin.render_component(componentId, props, 4));
+    const [like, reset] = findElements(ui, "Pressable");
+    TestPlugin.invoke_ui_event(reset.events.onPress[0], press);
+    renderFrame();
+    expect(!pageCounted(), "stopped press does not reach the page");
+    TestPlugin.invoke_ui_event(like.events.onPress[0], press);
+    renderFrame();
+    expect(pageCounted(), "press bubbles to the page");
+    const input = findElement(ui, "TextInput");
+    expect(TestPlugin.invoke_ui_event(input.events.onSubmitEditing[0], JSON.stringify({ type: "onSubmitEditing", text: "Ada" })), "prevented default is returned to the host");
+    TestPlugin.release_component_instance(componentId, 4);
+}
 await initializePlugin();
 await v3();
+await lifecycle();
+await instances();
+await textInput();
+await lists();
+await propagation();
@@ -1,3 +1,5 @@
+pub(crate) mod manifest;
+pub(crate) mod permissions;
 pub(crate) mod ui;
 
 use proc_macro::TokenStream;
@@ -195,3 +197,47 @@ pub fn ui_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
         #input
     }.into()
 }
+
+/// Guards the function with a permission check, returning `PermissionError` (converted via `From`)
+/// before the body runs. Add `prompt` to ask the user for missing permissions that can still be requested.
+/// Permissions not declared in the manifest generated by `#[plugin]` are reported at runtime
+///
+/// `#[requires(Permission::Internet, prompt)]`
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let permissions = syn::parse_macro_input!(
+        attr with syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated
+    );
+    let function = syn::parse_macro_input!(item as syn::ItemFn);
+
+    permissions::generate_requires(&get_crate_name(), permissions, function).into()
+}
+
+/// Marks the plugin entry point and generates the plugin manifest from the Cargo.toml metadata,
+/// the declared permissions and windows and every component registered with a literal UUID.
+/// The manifest is exported to the host as `get_plugin_manifest`
+///
+/// Components must be registered in the entry point with a literal id, e.g. `register_component::<T>(&uuid!("..."))`.
+/// Other ids are a compile error, and components registered elsewhere are reported at runtime,
+/// as are the permissions of `#[requires]` missing from `permission(...)`
+///
+/// ```ignore
+/// #[plugin(
+///     permission(name = "socigy.ui.components.replace", description = "...", required = true),
+///     window(id = "a005b6ce-a66e-4c81-924d-3d206e574718", description = "...")
+/// )]
+/// #[wasm_bindgen]
+/// pub fn main() {}
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn plugin(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = manifest::ManifestArgs::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    syn::parse_macro_input!(attr with parser);
+
+    let function = syn::parse_macro_input!(item as syn::ItemFn);
+
+    manifest::generate_plugin(&get_crate_name(), args, function).into()
+}
@@ -0,0 +1,273 @@
+use std::str::FromStr;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro_error::{abort, emit_error};
+use quote::quote;
+use serde_json::{json, Map, Value};
+use syn::{
+    meta::ParseNestedMeta,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, ExprLit, ItemFn, Lit, LitStr,
+};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct ManifestPermission {
+    name: Option<String>,
+    description: Option<String>,
+    link: Option<String>,
+    required: bool,
+    component_ids: Vec<String>,
+}
+
+#[derive(Default)]
+struct ManifestWindow {
+    id: Option<String>,
+    description: String,
+}
+
+#[derive(Default)]
+pub(crate) struct ManifestArgs {
+    permissions: Vec<ManifestPermission>,
+    windows: Vec<ManifestWindow>,
+}
+
+fn parse_uuid(lit: &LitStr) -> String {
+    let value = lit.value();
+    if Uuid::from_str(value.as_str()).is_err() {
+        abort!(lit.span(), "Expected a valid UUID");
+    }
+
+    value
+}
+
+fn parse_uuid_list(meta: &ParseNestedMeta) -> syn::Result<Vec<String>> {
+    let array: syn::ExprArray = meta.value()?.parse()?;
+
+    array
+        .elems
+        .iter()
+        .map(|element| match element {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Ok(parse_uuid(lit)),
+            _ => Err(syn::Error::new(element.span(), "Expected a UUID string")),
+        })
+        .collect()
+}
+
+impl ManifestArgs {
+    pub(crate) fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("permission") {
+            let mut permission = ManifestPermission::default();
+            meta.parse_nested_meta(|nested| {
+                if nested.path.is_ident("name") {
+                    permission.name = Some(nested.value()?.parse::<LitStr>()?.value());
+                } else if nested.path.is_ident("description") {
+                    permission.description = Some(nested.value()?.parse::<LitStr>()?.value());
+                } else if nested.path.is_ident("link") {
+                    permission.link = Some(nested.value()?.parse::<LitStr>()?.value());
+                } else if nested.path.is_ident("required") {
+                    permission.required = nested.value()?.parse::<syn::LitBool>()?.value;
+                } else if nested.path.is_ident("component_ids") {
+                    permission.component_ids = parse_uuid_list(&nested)?;
+                } else {
+                    return Err(nested.error("Unknown permission property. Expected one of name, description, link, required, component_ids"));
+                }
+
+                Ok(())
+            })?;
+
+            if permission.name.is_none() {
+                return Err(meta.error("The permission must have a 'name'"));
+            }
+
+            self.permissions.push(permission);
+            return Ok(());
+        }
+
+        if meta.path.is_ident("window") {
+            let mut window = ManifestWindow::default();
+            meta.parse_nested_meta(|nested| {
+                if nested.path.is_ident("id") {
+                    window.id = Some(parse_uuid(&nested.value()?.parse::<LitStr>()?));
+                } else if nested.path.is_ident("description") {
+                    window.description = nested.value()?.parse::<LitStr>()?.value();
+                } else {
+                    return Err(nested.error(
+                        "Unknown window property. Expected one of id, description",
+                    ));
+                }
+
+                Ok(())
+            })?;
+
+            if window.id.is_none() {
+                return Err(meta.error("The window must have an 'id'"));
+            }
+
+            self.windows.push(window);
+            return Ok(());
+        }
+
+        Err(meta.error("Unknown plugin property. Expected permission(...) or window(...)"))
+    }
+}
+
+/// Collects ids of all components registered with a literal UUID, e.g. `register_component::<T>(&uuid!("..."))`
+#[derive(Default)]
+struct RegisteredComponents {
+    ids: Vec<String>,
+}
+
+fn literal_uuid(expr: &Expr) -> Option<LitStr> {
+    match expr {
+        Expr::Reference(reference) => literal_uuid(&reference.expr),
+        Expr::Paren(paren) => literal_uuid(&paren.expr),
+        Expr::Macro(mac) if mac.mac.path.segments.last()?.ident == "uuid" => {
+            mac.mac.parse_body::<LitStr>().ok()
+        }
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Some(lit.clone()),
+        _ => None,
+    }
+}
+
+impl<'ast> Visit<'ast> for RegisteredComponents {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(path) = call.func.as_ref() {
+            let is_registration = path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "register_component");
+
+            if let Some(arg) = call.args.first().filter(|_| is_registration) {
+                match literal_uuid(arg) {
+                    Some(id) => {
+                        let id = parse_uuid(&id);
+                        if !self.ids.contains(&id) {
+                            self.ids.push(id);
+                        }
+                    }
+                    None => emit_error!(
+                        arg.span(),
+                        "Components must be registered with a literal id, e.g. `register_component::<T>(&uuid!(\"...\"))`, so the manifest can declare them"
+                    ),
+                }
+            }
+        }
+
+        visit::visit_expr_call(self, call);
+    }
+}
+
+fn env_or_default(name: &str) -> String {
+    std::env::var(name).unwrap_or_default()
+}
+
+fn generate_manifest_json(args: &ManifestArgs, components: &[String]) -> String {
+    let mut permissions = Map::new();
+    for permission in &args.permissions {
+        let mut value = Map::new();
+        value.insert(
+            "description".into(),
+            json!(permission.description.clone().unwrap_or_default()),
+        );
+        if let Some(link) = &permission.link {
+            value.insert("link".into(), json!(link));
+        }
+        value.insert("required".into(), json!(permission.required));
+        if !permission.component_ids.is_empty() {
+            value.insert("componentIds".into(), json!(permission.component_ids));
+        }
+
+        permissions.insert(
+            permission.name.clone().unwrap_or_default(),
+            Value::Object(value),
+        );
+    }
+
+    let windows: Map<String, Value> = args
+        .windows
+        .iter()
+        .map(|window| {
+            (
+                window.id.clone().unwrap_or_default(),
+                json!(window.description),
+            )
+        })
+        .collect();
+
+    let authors = split_authors(&env_or_default("CARGO_PKG_AUTHORS"));
+    let description = env_or_default("CARGO_PKG_DESCRIPTION");
+
+    let mut manifest = Map::new();
+    manifest.insert("name".into(), json!(env_or_default("CARGO_PKG_NAME")));
+    manifest.insert("version".into(), json!(env_or_default("CARGO_PKG_VERSION")));
+    manifest.insert("authors".into(), json!(authors));
+    if !description.trim().is_empty() {
+        manifest.insert("description".into(), json!(description.trim()));
+    }
+    manifest.insert("language".into(), json!("rust"));
+    manifest.insert("permissions".into(), Value::Object(permissions));
+    manifest.insert(
+        "ui".into(),
+        json!({
+            "components": components,
+            "windows": windows,
+        }),
+    );
+
+    Value::Object(manifest).to_string()
+}
+
+fn split_authors(value: &str) -> Vec<String> {
+    value
+        .split(':')
+        .filter(|author| !author.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+pub(crate) fn generate_plugin(lib_name: &Ident, args: ManifestArgs, function: ItemFn) -> TokenStream {
+    let mut components = RegisteredComponents::default();
+    components.visit_item_fn(&function);
+
+    let manifest = LitStr::new(
+        generate_manifest_json(&args, &components.ids).as_str(),
+        Span::call_site(),
+    );
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+    let statements = &block.stmts;
+
+    if !sig.inputs.is_empty() {
+        abort!(sig.inputs.span(), "The plugin entry point cannot take any arguments");
+    }
+
+    quote! {
+        #[doc(hidden)]
+        const __SOCIGY_PLUGIN_MANIFEST: &str = #manifest;
+
+        /// Returns the plugin manifest generated from the Cargo.toml metadata and the plugin code
+        #[#lib_name::exports::wasm_bindgen]
+        pub fn get_plugin_manifest() -> String {
+            __SOCIGY_PLUGIN_MANIFEST.to_string()
+        }
+
+        #(#attrs)*
+        #vis #sig {
+            #lib_name::manifest::set_manifest(__SOCIGY_PLUGIN_MANIFEST);
+
+            #(#statements)*
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{punctuated::Punctuated, spanned::Spanned, Expr, ItemFn, Token};
+
+fn is_prompt_flag(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(path) => path.path.is_ident("prompt"),
+        _ => false,
+    }
+}
+
+pub(crate) fn generate_requires(
+    lib_name: &Ident,
+    permissions: Punctuated<Expr, Token![,]>,
+    function: ItemFn,
+) -> TokenStream {
+    let mut prompt = false;
+    let mut required = vec![];
+    for permission in permissions {
+        if is_prompt_flag(&permission) {
+            prompt = true;
+        } else {
+            required.push(permission);
+        }
+    }
+
+    if required.is_empty() {
+        abort!(
+            function.sig.ident.span(),
+            "Expected at least one permission, e.g. #[requires(Permission::Internet)]"
+        );
+    }
+
+    if let syn::ReturnType::Default = function.sig.output {
+        abort!(
+            function.sig.span(),
+            "Functions guarded by #[requires] must return a Result with an error convertible from PermissionError"
+        );
+    }
+
+    let check = if prompt {
+        quote! { #lib_name::permissions::check_permissions_or_prompt }
+    } else {
+        quote! { #lib_name::permissions::check_permissions }
+    };
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+
+    quote! {
+        #(#attrs)*
+        #vis #sig {
+            {
+                #[allow(unused_imports)]
+                use #lib_name::permissions::Permission;
+                let required = [#(#required),*];
+                #lib_name::manifest::report_undeclared_permissions(&required);
+                #check(&required)?;
+            }
+
+            #block
+        }
+    }
+}
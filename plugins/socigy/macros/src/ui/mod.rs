@@ -1,5 +1,6 @@
 pub mod errors;
 pub mod input;
 pub mod output;
+pub mod tailwind;
 pub mod types;
 pub mod derive;
\ No newline at end of file
@@ -0,0 +1,628 @@
+//! Compile time validation of the literal `class_name` values against the Tailwind theme of the app.
+//!
+//! The theme tokens are exported from `client/native/app/tailwind.config.js` into `tailwind-theme.json`
+//! with `npm run export-tailwind-theme` in `plugins`. Plugins using a different theme can point
+//! the `SOCIGY_TAILWIND_THEME` environment variable to their own export.
+//!
+//! Cargo tracks neither the variable nor the file it points to, so the plugin must be built from clean,
+//! e.g. after `cargo clean -p socigy-macros`, once either of them changes.
+//!
+//! Classes the validation does not know, e.g. utilities of a Tailwind plugin, can be prefixed with `unchecked:`.
+//! The marker is removed from the class name passed to the element
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+const BUNDLED_THEME: &str = include_str!("../../tailwind-theme.json");
+
+/// Marks a class which is passed to the element without being validated, e.g. `unchecked:my-plugin-utility`
+const UNCHECKED_MARKER: &str = "unchecked:";
+
+/// Tokens of the Tailwind theme the utilities can use
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Theme {
+    colors: HashSet<String>,
+    color_palettes: HashSet<String>,
+    color_shades: HashSet<String>,
+    font_family: HashSet<String>,
+    font_weight: HashSet<String>,
+    font_size: HashSet<String>,
+    spacing: HashSet<String>,
+    border_radius: HashSet<String>,
+    border_width: HashSet<String>,
+}
+
+thread_local! {
+    static THEME: Result<Theme, String> = load_theme();
+}
+
+fn load_theme() -> Result<Theme, String> {
+    let content = match std::env::var("SOCIGY_TAILWIND_THEME") {
+        Ok(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read the Tailwind theme '{}': {}", path, e))?,
+        Err(_) => BUNDLED_THEME.to_string(),
+    };
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse the Tailwind theme: {}", e))
+}
+
+/// Utilities without a value
+const STATIC_UTILITIES: &[&str] = &[
+    "flex",
+    "hidden",
+    "contents",
+    "flex-row",
+    "flex-row-reverse",
+    "flex-col",
+    "flex-col-reverse",
+    "flex-wrap",
+    "flex-wrap-reverse",
+    "flex-nowrap",
+    "flex-1",
+    "flex-auto",
+    "flex-initial",
+    "flex-none",
+    "grow",
+    "grow-0",
+    "shrink",
+    "shrink-0",
+    "absolute",
+    "relative",
+    "static",
+    "visible",
+    "invisible",
+    "overflow-hidden",
+    "overflow-visible",
+    "overflow-scroll",
+    "italic",
+    "not-italic",
+    "uppercase",
+    "lowercase",
+    "capitalize",
+    "normal-case",
+    "underline",
+    "line-through",
+    "no-underline",
+    "truncate",
+    "border",
+    "border-x",
+    "border-y",
+    "border-t",
+    "border-r",
+    "border-b",
+    "border-l",
+    "border-solid",
+    "border-dashed",
+    "border-dotted",
+    "rounded",
+    "rounded-t",
+    "rounded-r",
+    "rounded-b",
+    "rounded-l",
+    "rounded-tl",
+    "rounded-tr",
+    "rounded-br",
+    "rounded-bl",
+    "shadow",
+    "shadow-sm",
+    "shadow-md",
+    "shadow-lg",
+    "shadow-xl",
+    "shadow-2xl",
+    "shadow-inner",
+    "shadow-none",
+    "aspect-auto",
+    "aspect-square",
+    "aspect-video",
+    "object-contain",
+    "object-cover",
+    "object-fill",
+    "object-none",
+    "object-scale-down",
+    "pointer-events-none",
+    "pointer-events-auto",
+    "select-none",
+    "select-text",
+    "select-all",
+    "select-auto",
+    "text-left",
+    "text-center",
+    "text-right",
+    "text-justify",
+    "text-start",
+    "text-end",
+    "align-auto",
+    "align-top",
+    "align-middle",
+    "align-bottom",
+    "group",
+    "peer",
+    "elevation",
+];
+
+/// Utilities with a value from a fixed scale, as `(prefix, values)`
+const SCALE_UTILITIES: &[(&str, &[&str])] = &[
+    ("items", &["start", "end", "center", "baseline", "stretch"]),
+    (
+        "justify",
+        &["start", "end", "center", "between", "around", "evenly"],
+    ),
+    (
+        "self",
+        &["auto", "start", "end", "center", "stretch", "baseline"],
+    ),
+    (
+        "content",
+        &[
+            "start", "end", "center", "between", "around", "evenly", "stretch",
+        ],
+    ),
+    (
+        "opacity",
+        &[
+            "0", "5", "10", "15", "20", "25", "30", "35", "40", "45", "50", "55", "60", "65", "70",
+            "75", "80", "85", "90", "95", "100",
+        ],
+    ),
+    ("z", &["0", "10", "20", "30", "40", "50", "auto"]),
+    (
+        "order",
+        &[
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "first", "last", "none",
+        ],
+    ),
+    (
+        "leading",
+        &[
+            "none", "tight", "snug", "normal", "relaxed", "loose", "3", "4", "5", "6", "7", "8",
+            "9", "10",
+        ],
+    ),
+    (
+        "tracking",
+        &["tighter", "tight", "normal", "wide", "wider", "widest"],
+    ),
+    ("line-clamp", &["1", "2", "3", "4", "5", "6", "none"]),
+    (
+        "rotate",
+        &["0", "1", "2", "3", "6", "12", "45", "90", "180"],
+    ),
+    (
+        "scale",
+        &[
+            "0", "50", "75", "90", "95", "100", "105", "110", "125", "150",
+        ],
+    ),
+    (
+        "scale-x",
+        &[
+            "0", "50", "75", "90", "95", "100", "105", "110", "125", "150",
+        ],
+    ),
+    (
+        "scale-y",
+        &[
+            "0", "50", "75", "90", "95", "100", "105", "110", "125", "150",
+        ],
+    ),
+    ("skew-x", &["0", "1", "2", "3", "6", "12"]),
+    ("skew-y", &["0", "1", "2", "3", "6", "12"]),
+    ("elevation", &["none", "sm", "md", "lg", "xl", "2xl"]),
+    (
+        "max-w",
+        &[
+            "none",
+            "xs",
+            "sm",
+            "md",
+            "lg",
+            "xl",
+            "2xl",
+            "3xl",
+            "4xl",
+            "5xl",
+            "6xl",
+            "7xl",
+            "full",
+            "min",
+            "max",
+            "fit",
+            "prose",
+            "screen-sm",
+            "screen-md",
+            "screen-lg",
+            "screen-xl",
+            "screen-2xl",
+        ],
+    ),
+];
+
+/// Utilities taking a color of the theme, e.g. `bg-background` or `text-red-500/50`
+const COLOR_UTILITIES: &[&str] = &[
+    "text",
+    "bg",
+    "border",
+    "border-x",
+    "border-y",
+    "border-t",
+    "border-r",
+    "border-b",
+    "border-l",
+    "tint",
+    "fill",
+    "stroke",
+    "shadow",
+    "ring",
+    "outline",
+    "decoration",
+    "placeholder",
+    "divide",
+    "caret",
+    "accent",
+    "from",
+    "via",
+    "to",
+];
+
+/// Utilities taking a spacing of the theme, e.g. `p-4`
+const SPACING_UTILITIES: &[&str] = &[
+    "p",
+    "px",
+    "py",
+    "pt",
+    "pr",
+    "pb",
+    "pl",
+    "ps",
+    "pe",
+    "m",
+    "mx",
+    "my",
+    "mt",
+    "mr",
+    "mb",
+    "ml",
+    "ms",
+    "me",
+    "gap",
+    "gap-x",
+    "gap-y",
+    "space-x",
+    "space-y",
+    "inset",
+    "inset-x",
+    "inset-y",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "start",
+    "end",
+    "translate-x",
+    "translate-y",
+    "basis",
+];
+
+/// Utilities which can be negated, e.g. `-mt-2`
+const NEGATIVE_UTILITIES: &[&str] = &[
+    "m",
+    "mx",
+    "my",
+    "mt",
+    "mr",
+    "mb",
+    "ml",
+    "ms",
+    "me",
+    "space-x",
+    "space-y",
+    "inset",
+    "inset-x",
+    "inset-y",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "start",
+    "end",
+    "translate-x",
+    "translate-y",
+    "rotate",
+    "skew-x",
+    "skew-y",
+    "order",
+    "z",
+];
+
+/// Utilities taking a spacing of the theme or a size, e.g. `w-full` or `h-1/2`
+const SIZE_UTILITIES: &[&str] = &["w", "h", "size", "min-w", "min-h", "max-h"];
+const SIZES: &[&str] = &["auto", "full", "screen", "min", "max", "fit"];
+
+/// Variants which can prefix a utility, e.g. `dark:bg-level-1`
+const VARIANTS: &[&str] = &[
+    "dark",
+    "light",
+    "ios",
+    "android",
+    "web",
+    "native",
+    "active",
+    "focus",
+    "hover",
+    "disabled",
+    "group-active",
+    "group-focus",
+    "group-hover",
+    "group-disabled",
+    "peer-active",
+    "peer-focus",
+    "peer-hover",
+    "peer-disabled",
+    "peer-checked",
+    "sm",
+    "md",
+    "lg",
+    "xl",
+    "2xl",
+];
+
+/// Returns the class names with the `unchecked:` markers removed, or `None` if there are no markers
+pub(crate) fn strip_unchecked_markers(class_name: &str) -> Option<String> {
+    if !class_name.contains(UNCHECKED_MARKER) {
+        return None;
+    }
+
+    Some(
+        class_name
+            .split_whitespace()
+            .map(|class| class.strip_prefix(UNCHECKED_MARKER).unwrap_or(class))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Returns the classes of the value which are not known to the theme, with the reason why
+pub(crate) fn invalid_classes(class_name: &str) -> Result<Vec<(String, String)>, String> {
+    THEME.with(|theme| {
+        let theme = theme.as_ref().map_err(|e| e.clone())?;
+
+        Ok(class_name
+            .split_whitespace()
+            .filter(|class| !class.starts_with(UNCHECKED_MARKER))
+            .filter_map(|class| {
+                validate_class(theme, class)
+                    .err()
+                    .map(|reason| (class.to_string(), reason))
+            })
+            .collect())
+    })
+}
+
+fn validate_class(theme: &Theme, class: &str) -> Result<(), String> {
+    let mut utility = class;
+    while let Some((variant, rest)) = utility.split_once(':') {
+        if !VARIANTS.contains(&variant) {
+            return Err(format!("unknown variant '{}'", variant));
+        }
+        utility = rest;
+    }
+
+    let utility = utility.strip_prefix('!').unwrap_or(utility);
+    let (negative, utility) = match utility.strip_prefix('-') {
+        Some(utility) => (true, utility),
+        None => (false, utility),
+    };
+
+    if STATIC_UTILITIES.contains(&utility) && !negative {
+        return Ok(());
+    }
+
+    // Prefixes are matched from the longest, so `border-t-2` is not read as the `border` color `t-2`
+    let mut prefixes = utility
+        .match_indices('-')
+        .map(|(index, _)| (&utility[..index], &utility[index + 1..]))
+        .collect::<Vec<_>>();
+    prefixes.reverse();
+
+    for (prefix, value) in prefixes {
+        // Arbitrary values are not checked, e.g. `w-[13px]`
+        if value.starts_with('[') && value.ends_with(']') {
+            return Ok(());
+        }
+
+        if let Some(result) = validate_utility(theme, prefix, value, negative) {
+            return result;
+        }
+    }
+
+    Err("unknown utility".into())
+}
+
+/// Returns `None` if the prefix is not a utility taking a value
+fn validate_utility(
+    theme: &Theme,
+    prefix: &str,
+    value: &str,
+    negative: bool,
+) -> Option<Result<(), String>> {
+    let result = validate_value(theme, prefix, value)?;
+    if negative && !NEGATIVE_UTILITIES.contains(&prefix) {
+        return Some(Err(format!("'{}' can not be negative", prefix)));
+    }
+
+    Some(result)
+}
+
+fn validate_value(theme: &Theme, prefix: &str, value: &str) -> Option<Result<(), String>> {
+    if let Some((_, values)) = SCALE_UTILITIES.iter().find(|(name, _)| *name == prefix) {
+        return Some(if values.contains(&value) {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not a value of '{}'", value, prefix))
+        });
+    }
+
+    if prefix == "font" {
+        return Some(
+            if theme.font_family.contains(value) || theme.font_weight.contains(value) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{}' is not a font family or weight of the theme",
+                    value
+                ))
+            },
+        );
+    }
+    if prefix == "text" && theme.font_size.contains(value) {
+        return Some(Ok(()));
+    }
+    if prefix.starts_with("rounded") && theme.border_radius.contains(value) {
+        return Some(Ok(()));
+    }
+    if prefix.starts_with("border") && theme.border_width.contains(value) {
+        return Some(Ok(()));
+    }
+
+    if SPACING_UTILITIES.contains(&prefix) {
+        return Some(
+            if theme.spacing.contains(value) || is_fraction(value) || value == "auto" {
+                Ok(())
+            } else {
+                Err(format!("'{}' is not a spacing of the theme", value))
+            },
+        );
+    }
+    if SIZE_UTILITIES.contains(&prefix) {
+        return Some(
+            if theme.spacing.contains(value) || is_fraction(value) || SIZES.contains(&value) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{}' is not a spacing of the theme or a size",
+                    value
+                ))
+            },
+        );
+    }
+
+    if COLOR_UTILITIES.contains(&prefix) {
+        return Some(if is_color(theme, value) {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not a color of the theme", value))
+        });
+    }
+
+    None
+}
+
+fn is_fraction(value: &str) -> bool {
+    value
+        .split_once('/')
+        .is_some_and(|(numerator, denominator)| {
+            numerator.parse::<u8>().is_ok() && denominator.parse::<u8>().is_ok()
+        })
+}
+
+/// Accepts the colors of the theme and the shades of the palettes, optionally with an opacity, e.g. `level-1/50`
+fn is_color(theme: &Theme, value: &str) -> bool {
+    let color = match value.split_once('/') {
+        Some((color, opacity)) if opacity.parse::<u8>().is_ok_and(|opacity| opacity <= 100) => {
+            color
+        }
+        Some(_) => return false,
+        None => value,
+    };
+
+    if theme.colors.contains(color) {
+        return true;
+    }
+
+    color.rsplit_once('-').is_some_and(|(palette, shade)| {
+        theme.color_palettes.contains(palette) && theme.color_shades.contains(shade)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(class: &str) -> Result<(), String> {
+        let theme: Theme = serde_json::from_str(BUNDLED_THEME).unwrap();
+        validate_class(&theme, class)
+    }
+
+    #[test]
+    fn variants_prefix_known_utilities() {
+        assert_eq!(validate("dark:bg-level-1"), Ok(()));
+        assert_eq!(validate("ios:active:text-foreground"), Ok(()));
+        assert_eq!(validate("group-hover:opacity-50"), Ok(()));
+        assert_eq!(validate("peer-focus:border-blue-500"), Ok(()));
+        assert!(validate("tablet:flex").is_err());
+        assert!(validate("dark:bg-unknown").is_err());
+    }
+
+    #[test]
+    fn only_some_utilities_can_be_negative() {
+        assert_eq!(validate("-mt-2"), Ok(()));
+        assert_eq!(validate("-translate-x-1/2"), Ok(()));
+        assert_eq!(validate("md:-z-10"), Ok(()));
+        assert!(validate("-p-2").is_err());
+        assert!(validate("-flex").is_err());
+    }
+
+    #[test]
+    fn colors_accept_an_opacity() {
+        assert_eq!(validate("bg-level-1/50"), Ok(()));
+        assert_eq!(validate("text-red-500/100"), Ok(()));
+        assert!(validate("bg-level-1/101").is_err());
+        assert!(validate("bg-red-501").is_err());
+        assert!(validate("bg-level-1/half").is_err());
+    }
+
+    #[test]
+    fn sizes_and_spacings_accept_fractions() {
+        assert_eq!(validate("w-1/2"), Ok(()));
+        assert_eq!(validate("basis-2/3"), Ok(()));
+        assert_eq!(validate("h-full"), Ok(()));
+        assert!(validate("w-half").is_err());
+        assert!(validate("w-1/x").is_err());
+    }
+
+    #[test]
+    fn the_longest_utility_prefix_is_used() {
+        // `border-t` with a width, not the `border` color `t-2`
+        assert_eq!(validate("border-t-2"), Ok(()));
+        assert_eq!(validate("border-t-red-500"), Ok(()));
+        assert_eq!(validate("scale-x-50"), Ok(()));
+        assert!(validate("scale-x-51").is_err());
+        assert!(validate("border-t-3").is_err());
+    }
+
+    #[test]
+    fn marker_utilities_and_elevations_are_known() {
+        assert_eq!(validate("group"), Ok(()));
+        assert_eq!(validate("peer"), Ok(()));
+        assert_eq!(validate("elevation"), Ok(()));
+        assert_eq!(validate("elevation-md"), Ok(()));
+        assert!(validate("elevation-3xl").is_err());
+    }
+
+    #[test]
+    fn arbitrary_values_are_not_checked() {
+        assert_eq!(validate("w-[13px]"), Ok(()));
+        assert_eq!(validate("bg-[#ff0000]"), Ok(()));
+    }
+
+    #[test]
+    fn unchecked_markers_are_stripped() {
+        assert_eq!(strip_unchecked_markers("p-2 flex"), None);
+        assert_eq!(
+            strip_unchecked_markers("p-2  unchecked:my-utility flex").as_deref(),
+            Some("p-2 my-utility flex")
+        );
+    }
+}
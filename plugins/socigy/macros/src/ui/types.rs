@@ -6,12 +6,25 @@ use std::{
 
 use proc_macro::{Group, Literal};
 use proc_macro2::{Ident, Span};
-use proc_macro_error::abort;
+use proc_macro_error::{abort, emit_error};
 use quote::{format_ident, quote, IdentFragment, ToTokens, TokenStreamExt};
 use uuid::Uuid;
 
+use super::tailwind;
+
 thread_local! {
-    static SOCIGY_ELEMENTS: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::from(["View","Text","FlatList","FlashList","TouchableOpacity"]));
+    static SOCIGY_ELEMENTS: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::from([
+        "View",
+        "Text",
+        "Pressable",
+        "TouchableOpacity",
+        "ScrollView",
+        "Image",
+        "TextInput",
+        "FlatList",
+        "SafeAreaView",
+        "FlashList",
+    ]));
 }
 
 #[derive(Debug, Clone)]
@@ -109,10 +122,15 @@ impl ToTokens for JsxAttributeValue {
             }
             JsxAttributeValue::Literal(literal) => {
                 let lit_str = literal.to_string();
-                let lit_c_str: &str = lit_str.trim_matches('\'').trim_matches('"');
 
-                if lit_str.starts_with('"') && lit_str.ends_with('"') {}
+                // Numbers and booleans keep their type, so they can be passed to the numeric props
+                if !lit_str.starts_with(['"', '\'']) {
+                    let literal: proc_macro2::TokenStream = literal.to_string().parse().unwrap();
+                    tokens.append_all(literal);
+                    return;
+                }
 
+                let lit_c_str: &str = lit_str.trim_matches('\'').trim_matches('"');
                 tokens.append_all(quote! {
                     #lit_c_str
                 });
@@ -162,12 +180,47 @@ fn to_camel_case(input: &str) -> String {
 
     result
 }
+/// Returns the payload type the listener of the event attribute receives, e.g. `ScrollEvent` for `on_scroll`.
+/// Capture listeners, e.g. `on_press_capture`, receive the payload of their event. Unknown events receive the whole `UIEvent`
+fn event_payload(attribute: &str) -> proc_macro2::TokenStream {
+    let event = attribute.strip_suffix("_capture").unwrap_or(attribute);
+    let payload = match event {
+        "on_layout" => "LayoutEvent",
+        "on_touch_start" | "on_touch_end" | "on_touch_move" | "on_touch_end_capture"
+        | "on_touch_cancel" => "TouchEvent",
+        "on_press" | "on_long_press" | "on_press_in" | "on_press_out" => "PressEvent",
+        "on_change_text" | "on_submit_editing" => "TextEvent",
+        "on_focus" | "on_blur" => "FocusEvent",
+        "on_key_press" => "KeyPressEvent",
+        "on_scroll" | "on_scroll_begin_drag" | "on_scroll_end_drag" | "on_momentum_scroll_end" => {
+            "ScrollEvent"
+        }
+        "on_end_reached" => "EndReachedEvent",
+        "on_load" => "ImageLoadEvent",
+        "on_error" => "ImageErrorEvent",
+        _ => "UIEvent",
+    };
+
+    let payload = format_ident!("{}", payload);
+    quote! { ::socigy::ui::events::#payload }
+}
 impl ToTokens for JsxAttribute {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name_ident =
             proc_macro2::Ident::new(&self.name, self.span.unwrap_or(Span::call_site()));
 
         let value = &self.value;
+        if let JsxAttributeValue::Literal(literal) = value {
+            if self.name == "class_name" || self.name.ends_with("_class_name") {
+                if let Some(class_name) = validate_class_name(literal) {
+                    tokens.append_all(quote! {
+                        #name_ident(#class_name.into())
+                    });
+                    return;
+                }
+            }
+        }
+
         match value {
             // Events are handled in the upper level and thus should not be called with the other attributes
             JsxAttributeValue::Event(_) => {
@@ -175,6 +228,16 @@ impl ToTokens for JsxAttribute {
                     #value
                 });
             }
+            JsxAttributeValue::Group(group) if self.name == "style" => {
+                match style_object(group) {
+                    Some(style) => tokens.append_all(quote! {
+                        #name_ident(#style.into())
+                    }),
+                    None => tokens.append_all(quote! {
+                        #name_ident(#value.into())
+                    }),
+                }
+            }
             _ => tokens.append_all(quote! {
                 #name_ident(#value.into())
             }),
@@ -182,6 +245,81 @@ impl ToTokens for JsxAttribute {
     }
 }
 
+/// Reports the classes of a literal `class_name` which are not known to the Tailwind theme of the app.
+/// Returns the literal without the `unchecked:` markers, if it has any
+fn validate_class_name(literal: &Literal) -> Option<proc_macro2::Literal> {
+    let value = literal.to_string();
+    if !value.starts_with('"') {
+        return None;
+    }
+
+    let class_name = value.trim_matches('"');
+    match tailwind::invalid_classes(class_name) {
+        Ok(invalid) => {
+            for (class, reason) in invalid {
+                emit_error!(
+                    literal.span(),
+                    "Invalid class '{}': {}. Prefix it with `unchecked:` to skip the validation",
+                    class,
+                    reason
+                );
+            }
+        }
+        Err(e) => abort!(literal.span(), e),
+    }
+
+    let mut stripped =
+        proc_macro2::Literal::string(&tailwind::strip_unchecked_markers(class_name)?);
+    stripped.set_span(literal.span().into());
+    Some(stripped)
+}
+
+/// Converts the object syntax `style={{ flex: 1, padding: 8 }}` into the style builder.
+/// Returns `None` if the value is a regular expression, e.g. `style={style.clone()}`
+fn style_object(group: &Group) -> Option<proc_macro2::TokenStream> {
+    use proc_macro2::{Spacing, TokenTree};
+
+    // The parser passes the inner braces of `{{ ... }}` as the value
+    if group.delimiter() != proc_macro::Delimiter::Brace {
+        return None;
+    }
+
+    let tokens = proc_macro2::TokenStream::from(group.stream())
+        .into_iter()
+        .collect::<Vec<_>>();
+    match (tokens.first(), tokens.get(1)) {
+        (Some(TokenTree::Ident(_)), Some(TokenTree::Punct(punct)))
+            if punct.as_char() == ':' && punct.spacing() == Spacing::Alone => {}
+        _ => return None,
+    }
+
+    let mut setters = proc_macro2::TokenStream::new();
+    for entry in tokens.split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ',')) {
+        if entry.is_empty() {
+            continue;
+        }
+
+        match entry {
+            [TokenTree::Ident(name), TokenTree::Punct(punct), value @ ..]
+                if punct.as_char() == ':' && punct.spacing() == Spacing::Alone && !value.is_empty() =>
+            {
+                let value = value.iter().cloned().collect::<proc_macro2::TokenStream>();
+                setters.append_all(quote! {
+                    .#name((#value).into())
+                });
+            }
+            _ => abort!(
+                entry[0].span(),
+                "Expected `property: value` entries in the style object"
+            ),
+        }
+    }
+
+    Some(quote! {
+        ::socigy::ui::style::StyleBuilder::default()#setters.build()
+    })
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct JsxElement {
     pub tag: Option<String>,
@@ -338,9 +476,10 @@ impl ToTokens for JsxElement {
 
                             if key.starts_with("on_") {
                                 let name = to_camel_case(key);
+                                let payload = event_payload(key);
                                 let attr_value = &value.value;
                                 events.append_all(quote! {
-                                    .add_event_listener_self(#name, Box::new(#attr_value))
+                                    .add_event_listener_self(#name, ::socigy::ui::events::listener::<#payload, _>(#attr_value))
                                 });
                             } else {
                                 attributes.push(value);
@@ -429,19 +568,26 @@ impl ToTokens for JsxElement {
                 _ => {
                     let builder_ident = format_ident!("{}Builder", tag_ident);
                     eprintln!("Package Element {}, has_children {}", builder_ident.to_string(), children_empty);
+                    let key_attribute = match &self.attributes {
+                        Some(attributes) => self.output_key_prop(attributes),
+                        None => quote! { None },
+                    };
                     let props = match &self.attributes {
                         Some(attr) => {
-                            let attributes: Vec<&JsxAttribute> =
-                                attr.into_iter().map(|(_, v)| v).collect();
+                            let attributes: Vec<&JsxAttribute> = attr
+                                .iter()
+                                .filter(|(k, _)| *k != "key")
+                                .map(|(_, v)| v)
+                                .collect();
 
                             if children_empty {
                                 quote! {
-                                    #builder_ident::default().#(#attributes).*.build()
+                                    #builder_ident::default()#(.#attributes)*.build()
                                 }
                             } else {
                                 quote! {
                                     
-                                    #builder_ident::default().children(#children.unwrap()).#(#attributes).*.build()
+                                    #builder_ident::default().children(#children.unwrap())#(.#attributes)*.build()
                                 }
                             }
                         }
@@ -459,7 +605,7 @@ impl ToTokens for JsxElement {
                     };
 
                     tokens.append_all(quote! {
-                        #props.render().into()
+                        ::socigy::ui::components::render_child(#props, #key_attribute).into()
                     });
                     return;
                 }
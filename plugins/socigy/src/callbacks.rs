@@ -2,7 +2,7 @@ use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
 
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::utils::{crypto::random_v4_uuid_str, json::parse_json};
+use crate::utils::crypto::random_v4_uuid_str;
 
 #[derive(Debug)]
 pub enum CallbackError {
@@ -65,25 +65,26 @@ pub fn register_callback_with_id(id: String, callback: Box<dyn Any>) {
 
 #[wasm_bindgen]
 pub fn invoke_rust_callback(id: String, args: String) {
-    REGISTERED_CALLBACKS.with_borrow_mut(|callbacks| {
-        if let Some(cb) = callbacks.remove(&id) {
-            if let Ok(function) = cb.downcast::<Box<dyn FnOnce(String)>>() {
-                function(
-                    parse_json(&args.as_str())
-                        .expect("Wrong JS arguments were passed down to the rust_callback"),
-                )
-            }
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("callback", callback_id = %id).entered();
+
+    // The borrow must be released before invoking, as the callback can register new callbacks
+    let callback = REGISTERED_CALLBACKS.with_borrow_mut(|callbacks| callbacks.remove(&id));
+    if let Some(cb) = callback {
+        if let Ok(function) = cb.downcast::<Box<dyn FnOnce(String)>>() {
+            function(args)
         }
-    });
+    }
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! invoke_native_for_user_consumption {
     ($internal_callback:expr, $callback:expr, $expect:literal, $( $arg:expr ),*) => {
-        let callback_id = $crate::callbacks::register_callback($crate::callback!(move |result: String| {
+        let callback: Box<dyn FnOnce(String)> = $crate::callback!(move |result: String| {
             $callback($crate::utils::json::parse_json(result.as_str()).expect($expect))
-        }));
+        });
+        let callback_id = $crate::callbacks::register_callback(Box::new(callback));
 
         $internal_callback(callback_id, $( $arg ),*)
     };
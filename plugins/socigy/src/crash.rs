@@ -0,0 +1,164 @@
+use std::{any::Any, cell::RefCell, panic::PanicHookInfo};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+use crate::{
+    logging::{self, LogLevel, LogRecord},
+    manifest,
+};
+
+#[wasm_bindgen(js_namespace = ["socigy", "logging"])]
+extern "C" {
+    #[wasm_bindgen(js_name = crash)]
+    fn internal_crash(report: JsValue, ui_delay: Option<u32>);
+}
+
+type CrashHook = Box<dyn Fn(&mut CrashReport)>;
+
+thread_local! {
+    static CRASH_CONTEXT: RefCell<Map<String, Value>> = RefCell::new(Map::new());
+    static CRASH_HOOK: RefCell<Option<CrashHook>> = const { RefCell::new(None) };
+}
+
+/// Target of the records logged when the plugin crashes
+pub const CRASH_TARGET: &str = "panic";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+impl std::fmt::Display for CrashLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Report sent to the host when the plugin panics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub message: String,
+    pub location: Option<CrashLocation>,
+    pub plugin_name: Option<String>,
+    pub plugin_version: Option<String>,
+    /// Id of the component which was rendering when the panic happened
+    pub component_id: Option<String>,
+    /// The most recent log records, oldest first
+    pub breadcrumbs: Vec<LogRecord>,
+    /// Custom values attached by the plugin with [`set_crash_context`] or [`set_crash_hook`]
+    pub context: Map<String, Value>,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: f64,
+}
+impl CrashReport {
+    pub fn new(message: String, location: Option<CrashLocation>) -> CrashReport {
+        let manifest = manifest::get_manifest();
+        let context = CRASH_CONTEXT
+            .try_with(|context| {
+                context
+                    .try_borrow()
+                    .map(|context| context.clone())
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        CrashReport {
+            message,
+            location,
+            plugin_name: manifest.as_ref().map(|manifest| manifest.name.clone()),
+            plugin_version: manifest.map(|manifest| manifest.version),
+            component_id: logging::current_component(),
+            breadcrumbs: logging::breadcrumbs(),
+            context,
+            timestamp: js_sys::Date::now(),
+        }
+    }
+
+    pub fn from_panic(info: &PanicHookInfo) -> CrashReport {
+        let location = info.location().map(|location| CrashLocation {
+            file: location.file().to_string(),
+            line: location.line(),
+            column: location.column(),
+        });
+
+        CrashReport::new(panic_message(info.payload()), location)
+    }
+}
+
+/// Extracts the message of a panic payload. Both `panic!("literal")` and formatted panics are supported
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("No panic message available")
+    }
+}
+
+/// Attaches a value to every future crash report
+pub fn set_crash_context(key: &str, value: Value) {
+    CRASH_CONTEXT.with_borrow_mut(|context| {
+        context.insert(key.to_string(), value);
+    });
+}
+
+pub fn remove_crash_context(key: &str) {
+    CRASH_CONTEXT.with_borrow_mut(|context| {
+        context.remove(key);
+    });
+}
+
+/// Registers a hook invoked with every crash report before it is sent to the host.
+/// The hook must not panic, as it runs inside of the panic handler
+pub fn set_crash_hook(hook: Box<dyn Fn(&mut CrashReport)>) {
+    CRASH_HOOK.with_borrow_mut(|crash_hook| {
+        crash_hook.replace(hook);
+    });
+}
+
+pub fn clear_crash_hook() {
+    CRASH_HOOK.with_borrow_mut(|crash_hook| {
+        crash_hook.take();
+    });
+}
+
+/// Runs the crash hook, logs the report as a fatal record and sends it to the host, which terminates the plugin
+pub fn report_crash(mut report: CrashReport, ui_delay: Option<u32>) {
+    let _ = CRASH_HOOK.try_with(|hook| {
+        if let Ok(hook) = hook.try_borrow() {
+            if let Some(hook) = hook.as_ref() {
+                hook(&mut report);
+            }
+        }
+    });
+
+    let mut record = LogRecord::new(LogLevel::Fatal, CRASH_TARGET, report.message.clone());
+    if let Some(location) = &report.location {
+        record = record.with_field("location", location.to_string().into());
+    }
+    logging::log_record(record);
+
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    match report.serialize(&serializer) {
+        Ok(value) => internal_crash(value, ui_delay),
+        Err(e) => logging::adv_fatal(
+            format!(
+                "{}\r\nFailed to serialize the crash report -> {}",
+                report.message, e
+            )
+            .as_str(),
+            ui_delay,
+        ),
+    }
+}
+
+/// Invoked by the panic hook. Plugins are built with `panic = "abort"`, so every panic crashes the whole plugin
+pub(crate) fn handle_panic(info: &PanicHookInfo) {
+    report_crash(CrashReport::from_panic(info), None);
+}
@@ -1,28 +1,10 @@
 use std::panic;
 
-use crate::logging::{adv_fatal, log};
+use crate::{crash::handle_panic, logging::log};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 fn register_panic_handler() {
-    panic::set_hook(Box::new(|info| {
-        let location = info.location().expect("There was no location info!");
-        adv_fatal(
-            (format!(
-                "{:#?}\r\nPlugin panicked at {}",
-                info.payload()
-                    .downcast_ref::<String>()
-                    .unwrap_or(&"No panic message available".to_string()),
-                format!(
-                    "{}:{}:{}",
-                    location.file(),
-                    location.line(),
-                    location.column()
-                )
-            ))
-            .as_str(),
-            None,
-        );
-    }));
+    panic::set_hook(Box::new(handle_panic));
 }
 
 #[wasm_bindgen]
@@ -33,6 +15,12 @@ pub fn initialize() {
         "Initializing Socigy Rust Core",
     );
     register_panic_handler();
+
+    #[cfg(feature = "log")]
+    crate::logging::install_log_adapter();
+    #[cfg(feature = "tracing")]
+    crate::logging::install_tracing_layer();
+
     log(
         crate::logging::LogLevel::Info,
         "Initialized Socigy Rust Core",
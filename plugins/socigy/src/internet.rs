@@ -1,7 +1,7 @@
 use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::permissions::PermissionError;
+use crate::permissions::{requires, PermissionError};
 
 #[wasm_bindgen(js_namespace = ["socigy", "internet"])]
 extern "C" {
@@ -15,14 +15,20 @@ pub struct FetchOptions {}
 #[derive(Debug)]
 pub enum FetchError {
     AccessDenied(PermissionError),
+    /// The host does not provide the `socigy.internet` binding yet
+    NotImplemented,
+}
+impl From<PermissionError> for FetchError {
+    fn from(value: PermissionError) -> Self {
+        FetchError::AccessDenied(value)
+    }
 }
 
+#[requires(Permission::Internet)]
 pub fn fetch<T>() -> Result<T, FetchError>
 where
     T: DeserializeOwned,
 {
-    Err(FetchError::AccessDenied(PermissionError {
-        permission: "socigy.internet".into(),
-        message: "Permission was not allowed".into(),
-    }))
+    // TODO: Wire the host fetch once the socigy.internet binding is available
+    Err(FetchError::NotImplemented)
 }
@@ -1,8 +1,10 @@
 pub mod callbacks;
 pub mod constants;
+pub mod crash;
 pub mod db;
 pub mod internet;
 pub mod logging;
+pub mod manifest;
 pub mod modals;
 pub mod notifications;
 pub mod payments;
@@ -0,0 +1,50 @@
+use super::{enabled, log_record, LogLevel, LogRecord};
+
+/// Forwards records of the `log` facade to the host, so output of third-party crates is not lost
+pub struct SocigyLogger;
+
+static LOGGER: SocigyLogger = SocigyLogger;
+
+impl From<log::Level> for LogLevel {
+    fn from(value: log::Level) -> Self {
+        match value {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug | log::Level::Trace => LogLevel::Debug,
+        }
+    }
+}
+
+impl log::Log for SocigyLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        enabled(metadata.level().into(), metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut socigy_record = LogRecord::new(
+            record.level().into(),
+            record.target(),
+            record.args().to_string(),
+        );
+        if let (Some(file), Some(line)) = (record.file(), record.line()) {
+            socigy_record = socigy_record.with_field("location", format!("{}:{}", file, line).into());
+        }
+
+        log_record(socigy_record);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs [`SocigyLogger`] as the `log` facade logger. Does nothing if another logger is already installed
+pub fn install_log_adapter() {
+    if log::set_logger(&LOGGER).is_ok() {
+        // Filtering is done by the Socigy log levels, which can change at runtime
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+}
@@ -0,0 +1,474 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+#[cfg(feature = "log")]
+mod log_adapter;
+#[cfg(feature = "log")]
+pub use log_adapter::*;
+
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::*;
+
+#[wasm_bindgen(js_namespace = ["socigy", "logging"])]
+extern "C" {
+    #[wasm_bindgen(js_name = record)]
+    fn internal_record(record: String);
+
+    #[wasm_bindgen(js_name = error)]
+    fn internal_error(message: JsValue, show_alert: bool, ui_delay: Option<u32>);
+
+    #[wasm_bindgen(js_name = fatal)]
+    fn internal_fatal(message: JsValue, ui_delay: Option<u32>);
+}
+
+thread_local! {
+    static CURRENT_COMPONENT: RefCell<Option<String>> = const { RefCell::new(None) };
+    static LOG_FILTER: RefCell<LogFilter> = RefCell::new(LogFilter::default());
+    static BREADCRUMBS: RefCell<VecDeque<LogRecord>> = RefCell::new(VecDeque::with_capacity(BREADCRUMB_LIMIT));
+}
+
+/// Number of the most recent records kept for crash reports
+pub const BREADCRUMB_LIMIT: usize = 32;
+
+/// Minimum levels used to decide whether a record gets sent to the host
+struct LogFilter {
+    max_level: LogLevel,
+    targets: HashMap<String, LogLevel>,
+    /// Targets whose level was set by the host, which the plugin does not override
+    host_targets: HashSet<String>,
+}
+impl Default for LogFilter {
+    fn default() -> Self {
+        LogFilter {
+            max_level: default_level(),
+            targets: HashMap::new(),
+            host_targets: HashSet::new(),
+        }
+    }
+}
+impl LogFilter {
+    fn level_for(&self, target: &str) -> LogLevel {
+        self.configured_level(target).unwrap_or(self.max_level)
+    }
+
+    /// Returns the level of the most specific configured target, e.g. `my_plugin::ui` before `my_plugin`
+    fn configured_level(&self, target: &str) -> Option<LogLevel> {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| covers(prefix, target))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+    }
+
+    fn host_configured(&self, target: &str) -> bool {
+        self.host_targets
+            .iter()
+            .any(|prefix| covers(prefix, target))
+    }
+}
+
+/// Whether the target is the prefix target or one of its submodules
+fn covers(prefix: &str, target: &str) -> bool {
+    target == prefix
+        || target
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Debug builds log everything, release builds start at [`LogLevel::Info`]
+fn default_level() -> LogLevel {
+    if cfg!(debug_assertions) {
+        LogLevel::Debug
+    } else {
+        LogLevel::Info
+    }
+}
+
+/// Returns true if a record of the level and target would be sent to the host
+pub fn enabled(level: LogLevel, target: &str) -> bool {
+    LOG_FILTER.with_borrow(|filter| level >= filter.level_for(target))
+}
+
+/// Sets the minimum level of all targets without their own level
+pub fn set_max_level(level: LogLevel) {
+    LOG_FILTER.with_borrow_mut(|filter| filter.max_level = level);
+}
+
+pub fn max_level() -> LogLevel {
+    LOG_FILTER.with_borrow(|filter| filter.max_level)
+}
+
+/// Sets the minimum level of the target and all of its submodules, e.g. `my_plugin::network`
+pub fn set_target_level(target: &str, level: LogLevel) {
+    LOG_FILTER.with_borrow_mut(|filter| {
+        filter.targets.insert(target.to_string(), level);
+    });
+}
+
+/// Sets the minimum level of the target, unless the host configured the level of the target or of its parent
+pub fn set_default_target_level(target: &str, level: LogLevel) {
+    LOG_FILTER.with_borrow_mut(|filter| {
+        if !filter.host_configured(target) {
+            filter.targets.insert(target.to_string(), level);
+        }
+    });
+}
+
+pub fn clear_target_level(target: &str) {
+    LOG_FILTER.with_borrow_mut(|filter| {
+        filter.targets.remove(target);
+        filter.host_targets.remove(target);
+    });
+}
+
+/// Invoked by the host to change the minimum log level at runtime.
+/// Without a target the global level is changed
+#[wasm_bindgen]
+pub fn set_log_level(level: String, target: Option<String>) {
+    let level = match LogLevel::from_str(level.as_str()) {
+        Ok(level) => level,
+        Err(e) => {
+            adv_error(e.as_str(), None, false);
+            return;
+        }
+    };
+
+    match target {
+        Some(target) => LOG_FILTER.with_borrow_mut(|filter| {
+            filter.targets.insert(target.clone(), level);
+            filter.host_targets.insert(target);
+        }),
+        None => set_max_level(level),
+    }
+}
+
+/// Target used by records created without an explicit target, e.g. through [`log`]
+pub const DEFAULT_TARGET: &str = "console";
+
+/// Structured log record sent to the host as JSON, so the log viewer and Loki can filter by its fields
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_id: Option<String>,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: f64,
+}
+impl LogRecord {
+    pub fn new(level: LogLevel, target: &str, message: String) -> LogRecord {
+        LogRecord {
+            level,
+            target: target.to_string(),
+            message,
+            fields: Map::new(),
+            component_id: current_component(),
+            timestamp: js_sys::Date::now(),
+        }
+    }
+
+    pub fn with_field(mut self, key: &str, value: Value) -> LogRecord {
+        self.fields.insert(key.to_string(), value);
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<(&str, Value)>) -> LogRecord {
+        self.fields
+            .extend(fields.into_iter().map(|(key, value)| (key.to_string(), value)));
+        self
+    }
+}
+
+/// Converts a log field to a JSON value. Values that fail to serialize are logged as `null`
+#[doc(hidden)]
+pub fn field_value<T: Serialize + ?Sized>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Returns the id of the component that is currently being rendered
+pub fn current_component() -> Option<String> {
+    CURRENT_COMPONENT.with_borrow(|id| id.clone())
+}
+
+/// Attaches the component id to every record logged inside of the function
+pub fn with_component_scope<T>(id: String, function: impl FnOnce() -> T) -> T {
+    /// Restores the previous component even if the function panics
+    struct ScopeGuard(Option<String>);
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            CURRENT_COMPONENT.set(self.0.take());
+        }
+    }
+
+    let _guard = ScopeGuard(CURRENT_COMPONENT.replace(Some(id)));
+    function()
+}
+
+pub fn log_record(record: LogRecord) {
+    if !enabled(record.level, record.target.as_str()) {
+        return;
+    }
+
+    push_breadcrumb(&record);
+
+    match serde_json::to_string(&record) {
+        Ok(json) => internal_record(json),
+        Err(e) => internal_error(
+            JsValue::from_str(format!("Failed to serialize the log record: {}", e).as_str()),
+            false,
+            None,
+        ),
+    }
+}
+
+fn push_breadcrumb(record: &LogRecord) {
+    // The breadcrumbs can already be borrowed, if logging panicked
+    let _ = BREADCRUMBS.try_with(|breadcrumbs| {
+        if let Ok(mut breadcrumbs) = breadcrumbs.try_borrow_mut() {
+            if breadcrumbs.len() == BREADCRUMB_LIMIT {
+                breadcrumbs.pop_front();
+            }
+            breadcrumbs.push_back(record.clone());
+        }
+    });
+}
+
+/// Returns the most recent records sent to the host, oldest first
+pub fn breadcrumbs() -> Vec<LogRecord> {
+    BREADCRUMBS
+        .try_with(|breadcrumbs| {
+            breadcrumbs
+                .try_borrow()
+                .map(|breadcrumbs| breadcrumbs.iter().cloned().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+pub fn log(level: LogLevel, message: &str) {
+    log_record(LogRecord::new(level, DEFAULT_TARGET, message.to_string()));
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __target {
+    (target: $target:expr, $($arg:tt)+) => {
+        $target
+    };
+    ($($arg:tt)+) => {
+        module_path!()
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::logging::enabled($level, $crate::__target!($($arg)+)) {
+            $crate::logging::log_record($crate::__record!($level, $($arg)+));
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __record {
+    ($level:expr, target: $target:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {
+        $crate::logging::LogRecord::new($level, $target, format!($($arg)+))
+            .with_fields(vec![$((stringify!($key), $crate::logging::field_value(&$value))),+])
+    };
+    ($level:expr, target: $target:expr, $($arg:tt)+) => {
+        $crate::logging::LogRecord::new($level, $target, format!($($arg)+))
+    };
+    ($level:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {
+        $crate::__record!($level, target: module_path!(), $($key = $value),+ ; $($arg)+)
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::__record!($level, target: module_path!(), $($arg)+)
+    };
+}
+
+/// Logs a debug record. Accepts an optional `target: "name",` and `key = value;` fields before the message.
+/// The message is formatted only if the level is enabled for the target
+///
+/// `debug!(target: "network", status = 200, url = url; "Fetched {}", url)`
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::__log!($crate::logging::LogLevel::Debug, $($arg)+);
+    };
+}
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::__log!($crate::logging::LogLevel::Info, $($arg)+);
+    };
+}
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::__log!($crate::logging::LogLevel::Warn, $($arg)+);
+    };
+}
+/// Logs an error record and shows an alert to the user, if the level is enabled for the target
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        if $crate::logging::enabled($crate::logging::LogLevel::Error, $crate::__target!($($arg)+)) {
+            $crate::logging::report_error($crate::__record!($crate::logging::LogLevel::Error, $($arg)+), None, true);
+        }
+    };
+}
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)+) => {
+        if $crate::logging::enabled($crate::logging::LogLevel::Fatal, $crate::__target!($($arg)+)) {
+            $crate::logging::report_fatal($crate::__record!($crate::logging::LogLevel::Fatal, $($arg)+), None);
+        }
+    };
+}
+
+/// Logs the error record and optionally shows an alert to the user. Records filtered out by their level show no alert
+pub fn report_error(record: LogRecord, ui_delay: Option<u32>, show_alert: bool) {
+    if !enabled(record.level, record.target.as_str()) {
+        return;
+    }
+
+    let message = record.message.clone();
+    log_record(record);
+
+    if show_alert {
+        internal_error(JsValue::from_str(message.as_str()), show_alert, ui_delay);
+    }
+}
+/// Logs the fatal record and lets the host terminate the plugin
+pub fn report_fatal(record: LogRecord, ui_delay: Option<u32>) {
+    let message = record.message.clone();
+    log_record(record);
+
+    internal_fatal(JsValue::from_str(message.as_str()), ui_delay);
+}
+
+pub fn adv_error(message: &str, ui_delay: Option<u32>, show_alert: bool) {
+    report_error(
+        LogRecord::new(LogLevel::Error, DEFAULT_TARGET, message.to_string()),
+        ui_delay,
+        show_alert,
+    );
+}
+pub fn adv_fatal(message: &str, ui_delay: Option<u32>) {
+    report_fatal(
+        LogRecord::new(LogLevel::Fatal, DEFAULT_TARGET, message.to_string()),
+        ui_delay,
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogLevel::Debug => write!(f, "DEBUG"),
+            LogLevel::Info => write!(f, " INFO"),
+            LogLevel::Warn => write!(f, " WARN"),
+            LogLevel::Error => write!(f, "\x1b[31mERROR\x1b[0m"),
+            LogLevel::Fatal => write!(f, "\x1b[31mFATAL\x1b[0m"),
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            "fatal" => Ok(LogLevel::Fatal),
+            _ => Err(format!("Unknown log level '{}'", value)),
+        }
+    }
+}
+
+/// Logger bound to a single target, usually the module it is created in.
+/// Records below the configured level of the target, or the level of the logger if none is configured, are discarded
+pub struct Logger {
+    name: String,
+    level: LogLevel,
+}
+impl Logger {
+    pub fn new(name: Option<String>, level: LogLevel) -> Logger {
+        let name = match name {
+            Some(name) => name,
+            None => String::from("Default"),
+        };
+
+        Logger { name, level }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        let min_level = LOG_FILTER.with_borrow(|filter| {
+            filter
+                .configured_level(self.name.as_str())
+                .unwrap_or(self.level)
+        });
+        level >= min_level
+    }
+
+    /// Sets the minimum level of the target for all of its records, see [`set_default_target_level`]
+    pub fn set_level(&self, level: LogLevel) {
+        set_default_target_level(self.name.as_str(), level);
+    }
+
+    pub fn log(&self, level: LogLevel, message: &str) {
+        if self.enabled(level) {
+            log_record(LogRecord::new(
+                level,
+                self.name.as_str(),
+                message.to_string(),
+            ));
+        }
+    }
+
+    pub fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message);
+    }
+
+    pub fn info(&self, message: &str) {
+        self.log(LogLevel::Info, message);
+    }
+
+    pub fn warn(&self, message: &str) {
+        self.log(LogLevel::Warn, message);
+    }
+
+    pub fn error(&self, message: &str) {
+        self.log(LogLevel::Error, message);
+    }
+}
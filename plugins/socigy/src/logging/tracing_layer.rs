@@ -0,0 +1,157 @@
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    span,
+    subscriber::Interest,
+    Event, Metadata, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer, Registry};
+
+use super::{enabled, log_record, LogLevel, LogRecord};
+
+/// `tracing` layer forwarding events to the host. Closed spans are logged with their duration
+pub struct SocigyLayer;
+
+/// Fields and the start time of a span, stored in the span extensions
+struct SpanData {
+    fields: Map<String, Value>,
+    start: f64,
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                Value::String(message) => message,
+                value => value.to_string(),
+            });
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into());
+    }
+}
+
+impl From<&tracing::Level> for LogLevel {
+    fn from(value: &tracing::Level) -> Self {
+        match *value {
+            tracing::Level::ERROR => LogLevel::Error,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::INFO => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+impl<S> Layer<S> for SocigyLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    /// Levels change at runtime through `set_log_level`, so callsites must not cache a disabled interest
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        enabled(metadata.level().into(), metadata.target())
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanData {
+            fields: visitor.fields,
+            start: js_sys::Date::now(),
+        });
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            data.fields.extend(visitor.fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let mut record = LogRecord::new(
+            metadata.level().into(),
+            metadata.target(),
+            visitor.message.unwrap_or_default(),
+        );
+        record.fields = visitor.fields;
+        if let Some(span) = ctx.event_span(event) {
+            record = record.with_field("span", span.name().into());
+        }
+
+        log_record(record);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+
+        let metadata = span.metadata();
+        let duration = js_sys::Date::now() - data.start;
+        let mut record = LogRecord::new(
+            metadata.level().into(),
+            metadata.target(),
+            format!("{} finished in {:.2}ms", metadata.name(), duration),
+        );
+        record.fields = data.fields;
+
+        log_record(record.with_field("durationMs", duration.into()));
+    }
+}
+
+/// Installs a global `tracing` subscriber with the [`SocigyLayer`].
+/// Does nothing if another subscriber is already installed
+pub fn install_tracing_layer() {
+    let _ = tracing::subscriber::set_global_default(Registry::default().with(SocigyLayer));
+}
@@ -0,0 +1,103 @@
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{logging, permissions::Permission};
+
+pub use socigy_macros::plugin;
+
+thread_local! {
+    static MANIFEST: OnceCell<PluginManifest> = const { OnceCell::new() };
+    /// Permissions already reported as missing from the manifest
+    static REPORTED_PERMISSIONS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Plugin manifest generated by the [`plugin`] attribute. Mirrors `PluginConfig` of the host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub language: String,
+    pub permissions: HashMap<String, ManifestPermission>,
+    pub ui: ManifestUi,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestPermission {
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub component_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestUi {
+    pub components: Vec<Uuid>,
+    pub windows: HashMap<Uuid, String>,
+}
+
+/// Called by the code generated by the [`plugin`] attribute before the plugin entry point runs
+#[doc(hidden)]
+pub fn set_manifest(json: &str) {
+    let manifest = match serde_json::from_str::<PluginManifest>(json) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            logging::adv_error(
+                format!("Failed to parse the generated plugin manifest -> {}", e).as_str(),
+                None,
+                false,
+            );
+            return;
+        }
+    };
+
+    MANIFEST.with(|cell| {
+        let _ = cell.set(manifest);
+    });
+}
+
+/// Returns the manifest of the plugin, if the entry point is marked with the [`plugin`] attribute
+pub fn get_manifest() -> Option<PluginManifest> {
+    MANIFEST.with(|cell| cell.get().cloned())
+}
+
+/// Called by the code generated by the `requires` attribute. Reports every required permission
+/// which the manifest does not declare once, as the host never grants it
+#[doc(hidden)]
+pub fn report_undeclared_permissions(permissions: &[Permission]) {
+    for permission in permissions {
+        let name = permission.name();
+        let declared = MANIFEST.with(|cell| {
+            cell.get()
+                .map(|manifest| manifest.permissions.contains_key(name))
+        });
+        // Plugins without the manifest are not checked
+        if declared != Some(false)
+            || !REPORTED_PERMISSIONS.with_borrow_mut(|reported| reported.insert(name.to_string()))
+        {
+            continue;
+        }
+
+        logging::adv_error(
+            format!(
+                "Permission {} is required by the plugin code, but not declared in the plugin manifest. Declare it in the #[plugin] entry point",
+                name
+            )
+            .as_str(),
+            None,
+            false,
+        );
+    }
+}
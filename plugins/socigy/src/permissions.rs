@@ -1,8 +1,18 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
 use serde::Deserialize;
 use serde_wasm_bindgen::to_value;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
-use crate::invoke_native_for_user_consumption;
+use crate::{
+    invoke_native_for_user_consumption, logging, utils::crypto::random_v4_uuid_str,
+};
+
+pub use socigy_macros::requires;
 
 #[wasm_bindgen(js_namespace = ["socigy", "permissions"])]
 extern "C" {
@@ -16,7 +26,51 @@ extern "C" {
     fn internal_request_permissions_async(callbackId: String, permissions: JsValue);
 }
 
-#[derive(Debug, Deserialize)]
+type PermissionListener = Box<dyn FnMut(&PermissionChange)>;
+
+thread_local! {
+    static PERMISSION_CACHE: RefCell<HashMap<String, PermissionState>> = RefCell::new(HashMap::new());
+    static PERMISSION_LISTENERS: RefCell<HashMap<String, Rc<RefCell<PermissionListener>>>> = RefCell::new(HashMap::new());
+    /// Changes waiting for the listeners, which are notified one change at a time
+    static PENDING_CHANGES: RefCell<VecDeque<PermissionChange>> = const { RefCell::new(VecDeque::new()) };
+    static NOTIFYING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Permissions known to the Socigy host. Plugin specific permissions can be expressed with [`Permission::Custom`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Permission {
+    Internet,
+    UiComponentsReplace,
+    UiComponentsDelete,
+    Custom(String),
+}
+impl Permission {
+    pub fn name(&self) -> &str {
+        match self {
+            Permission::Internet => "socigy.internet",
+            Permission::UiComponentsReplace => "socigy.ui.components.replace",
+            Permission::UiComponentsDelete => "socigy.ui.components.delete",
+            Permission::Custom(name) => name.as_str(),
+        }
+    }
+}
+impl From<&str> for Permission {
+    fn from(value: &str) -> Self {
+        match value {
+            "socigy.internet" => Permission::Internet,
+            "socigy.ui.components.replace" => Permission::UiComponentsReplace,
+            "socigy.ui.components.delete" => Permission::UiComponentsDelete,
+            _ => Permission::Custom(value.to_string()),
+        }
+    }
+}
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct PermissionState {
     name: String,
     granted: bool,
@@ -45,6 +99,26 @@ impl PermissionState {
     }
 }
 
+/// Describes a single permission state change, e.g. the user revoking a permission in the app settings
+#[derive(Debug, Clone)]
+pub struct PermissionChange {
+    pub previous: Option<PermissionState>,
+    pub current: PermissionState,
+}
+impl PermissionChange {
+    pub fn permission(&self) -> Permission {
+        Permission::from(self.current.name().as_str())
+    }
+
+    pub fn was_revoked(&self) -> bool {
+        self.previous.as_ref().is_some_and(|p| p.granted()) && !self.current.granted()
+    }
+
+    pub fn was_granted(&self) -> bool {
+        !self.previous.as_ref().is_some_and(|p| p.granted()) && self.current.granted()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PermissionDeclaration {
     pub name: String,
@@ -52,7 +126,68 @@ pub struct PermissionDeclaration {
     pub required: bool,
 }
 
+/// Stores the new states and notifies the subscribers about every state that differs from the cached one.
+/// Permissions seen for the first time are reported only if `report_new` is set, as the initial query is not a change
+fn update_permission_cache(permissions: &[PermissionState], report_new: bool) {
+    let changes: Vec<PermissionChange> = PERMISSION_CACHE.with_borrow_mut(|cache| {
+        permissions
+            .iter()
+            .filter_map(|permission| {
+                let previous = cache.insert(permission.name.clone(), permission.clone());
+                match previous {
+                    Some(previous) if previous == *permission => None,
+                    None if !report_new => None,
+                    previous => Some(PermissionChange {
+                        previous,
+                        current: permission.clone(),
+                    }),
+                }
+            })
+            .collect()
+    });
+
+    PENDING_CHANGES.with_borrow_mut(|pending| pending.extend(changes));
+
+    // A listener requesting permissions is still running when the host answers synchronously.
+    // The nested changes are queued and delivered once the running listener returns
+    if NOTIFYING.replace(true) {
+        return;
+    }
+
+    /// Lets the next change notify the listeners even if a listener panics
+    struct NotifyingGuard;
+    impl Drop for NotifyingGuard {
+        fn drop(&mut self) {
+            NOTIFYING.set(false);
+        }
+    }
+
+    let _guard = NotifyingGuard;
+    while let Some(change) = PENDING_CHANGES.with_borrow_mut(|pending| pending.pop_front()) {
+        #[cfg(feature = "ui")]
+        crate::ui::hooks::permission_changed(change.current.name());
+
+        // Listeners are cloned out, so they can subscribe or unsubscribe while being notified
+        let listeners: Vec<Rc<RefCell<PermissionListener>>> =
+            PERMISSION_LISTENERS.with_borrow(|listeners| listeners.values().cloned().collect());
+        for listener in &listeners {
+            (listener.borrow_mut())(&change);
+        }
+    }
+}
+
+/// Wraps the user callback, so every host response refreshes the permission cache before the user sees it
+fn cache_before(
+    callback: Box<dyn FnOnce(Vec<PermissionState>)>,
+) -> Box<dyn FnOnce(Vec<PermissionState>)> {
+    Box::new(move |permissions: Vec<PermissionState>| {
+        update_permission_cache(&permissions, false);
+        callback(permissions)
+    })
+}
+
 pub fn get_permissions_async(callback: Box<dyn FnOnce(Vec<PermissionState>)>) {
+    let callback = cache_before(callback);
     invoke_native_for_user_consumption!(
         internal_get_permissions_async,
         callback,
@@ -74,6 +209,7 @@ pub fn request_permissions_async(
     permissions: &Vec<String>,
     callback: Box<dyn FnOnce(Vec<PermissionState>)>,
 ) {
+    let callback = cache_before(callback);
     invoke_native_for_user_consumption!(
         internal_request_permissions_async,
         callback,
@@ -82,14 +218,206 @@ pub fn request_permissions_async(
     );
 }
 
+/// Registers a listener invoked every time a permission state changes.
+/// Returns the subscription id used by [`unsubscribe_permission_changes`]
+pub fn subscribe_permission_changes(listener: Box<dyn FnMut(&PermissionChange)>) -> String {
+    let id = random_v4_uuid_str();
+    PERMISSION_LISTENERS.with_borrow_mut(|listeners| {
+        listeners.insert(id.clone(), Rc::new(RefCell::new(listener)));
+    });
+
+    id
+}
+
+/// Same as [`subscribe_permission_changes`], but the listener is invoked only for the specified permission
+pub fn subscribe_permission(
+    permission: Permission,
+    mut listener: Box<dyn FnMut(&PermissionChange)>,
+) -> String {
+    subscribe_permission_changes(Box::new(move |change| {
+        if change.current.name() == permission.name() {
+            listener(change)
+        }
+    }))
+}
+
+pub fn unsubscribe_permission_changes(id: &str) {
+    PERMISSION_LISTENERS.with_borrow_mut(|listeners| {
+        listeners.remove(id);
+    });
+}
+
+/// Invoked by the host whenever the user changes a permission of the plugin.
+/// Accepts a single `PermissionState` or an array of them
+#[wasm_bindgen]
+pub fn invoke_permission_change(states: String) {
+    let states = match serde_json::from_str::<PermissionStates>(states.as_str()) {
+        Ok(PermissionStates::Single(state)) => vec![state],
+        Ok(PermissionStates::Multiple(states)) => states,
+        Err(e) => {
+            logging::adv_error(
+                format!("Failed to deserialize PermissionState -> {}", e).as_str(),
+                None,
+                false,
+            );
+            return;
+        }
+    };
+
+    update_permission_cache(&states, true);
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PermissionStates {
+    Single(PermissionState),
+    Multiple(Vec<PermissionState>),
+}
+
+/// Returns the last known state of the permission. The cache is filled by [`get_permissions_async`] and [`request_permissions_async`].
+/// A component reading it renders again once the state changes
+pub fn get_cached_permission(permission: &Permission) -> Option<PermissionState> {
+    #[cfg(feature = "ui")]
+    crate::ui::hooks::record_consumed_permission(permission.name());
+
+    PERMISSION_CACHE.with_borrow(|cache| cache.get(permission.name()).cloned())
+}
+
+pub fn is_granted(permission: &Permission) -> bool {
+    get_cached_permission(permission).is_some_and(|state| state.granted())
+}
+
+/// Checks the cached state of the permission without calling the host
+pub fn check_permission(permission: &Permission) -> Result<(), PermissionError> {
+    match get_cached_permission(permission) {
+        Some(state) if state.granted() => Ok(()),
+        Some(state) => Err(PermissionError::denied(permission, state.can_ask_again())),
+        None => Err(PermissionError::unknown(permission)),
+    }
+}
+
+/// Checks all of the permissions and returns the first one that is not granted
+pub fn check_permissions(permissions: &[Permission]) -> Result<(), PermissionError> {
+    permissions.iter().try_for_each(check_permission)
+}
+
+/// Same as [`check_permissions`], but in case of failure asks the user for all of the
+/// missing permissions that can still be requested. The result of the prompt only updates the cache,
+/// so the next call of the guarded function can succeed
+pub fn check_permissions_or_prompt(permissions: &[Permission]) -> Result<(), PermissionError> {
+    let result = check_permissions(permissions);
+    if result.is_err() {
+        let requestable = requestable_permissions(permissions);
+        if !requestable.is_empty() {
+            request_permissions_async(&requestable, Box::new(|_| {}));
+        }
+    }
+
+    result
+}
+
+/// Invokes the function only if all of the permissions are granted
+pub fn with_permissions<T>(
+    permissions: &[Permission],
+    function: impl FnOnce() -> T,
+) -> Result<T, PermissionError> {
+    check_permissions(permissions)?;
+    Ok(function())
+}
+
+/// Makes sure all of the permissions are granted, asking the user for those which are missing
+/// and can still be requested. The callback receives the final result
+pub fn ensure_permissions_async(
+    permissions: Vec<Permission>,
+    callback: Box<dyn FnOnce(Result<(), PermissionError>)>,
+) {
+    if check_permissions(&permissions).is_ok() {
+        callback(Ok(()));
+        return;
+    }
+
+    let requestable = requestable_permissions(&permissions);
+    if requestable.is_empty() {
+        callback(check_permissions(&permissions));
+        return;
+    }
+
+    request_permissions_async(
+        &requestable,
+        Box::new(move |_| callback(check_permissions(&permissions))),
+    );
+}
+
+fn requestable_permissions(permissions: &[Permission]) -> Vec<String> {
+    permissions
+        .iter()
+        .filter(|permission| match get_cached_permission(permission) {
+            Some(state) => !state.granted() && state.can_ask_again(),
+            None => true,
+        })
+        .map(|permission| permission.name().to_string())
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct PermissionError {
     pub permission: String,
     pub message: String,
+    pub can_ask_again: bool,
+}
+impl PermissionError {
+    pub fn denied(permission: &Permission, can_ask_again: bool) -> PermissionError {
+        PermissionError {
+            permission: permission.name().to_string(),
+            message: "Permission was not allowed".into(),
+            can_ask_again,
+        }
+    }
+
+    pub fn unknown(permission: &Permission) -> PermissionError {
+        PermissionError {
+            permission: permission.name().to_string(),
+            message: "Permission state is unknown. Call get_permissions_async first".into(),
+            can_ask_again: true,
+        }
+    }
 }
 
 impl std::fmt::Display for PermissionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Permission errors")
+        write!(f, "Permission '{}' error: {}", self.permission, self.message)
+    }
+}
+impl std::error::Error for PermissionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(name: &str, granted: bool) -> PermissionState {
+        PermissionState::new(name.to_string(), granted, true)
+    }
+
+    #[test]
+    fn nested_changes_reach_every_listener_in_order() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        let requesting = subscribe_permission_changes(Box::new(|change| {
+            if change.current.name() == "test.first" {
+                update_permission_cache(&[state("test.second", true)], true);
+            }
+        }));
+        let inner_received = received.clone();
+        let recording = subscribe_permission_changes(Box::new(move |change| {
+            inner_received
+                .borrow_mut()
+                .push(change.current.name().clone());
+        }));
+
+        update_permission_cache(&[state("test.first", true)], true);
+        unsubscribe_permission_changes(&requesting);
+        unsubscribe_permission_changes(&recording);
+
+        assert_eq!(*received.borrow(), ["test.first", "test.second"]);
     }
 }
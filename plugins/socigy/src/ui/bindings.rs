@@ -1,10 +1,10 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::{info, logging};
+use crate::{info, logging, manifest};
 
 use super::{
-    components::{ComponentInstance, UIComponent},
-    renderer::Renderer,
+    components::{AnyComponent, AppState, ComponentInstance, UIComponent},
+    renderer::{InstanceId, Renderer},
 };
 use uuid::Uuid;
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -17,14 +17,34 @@ extern "C" {
     fn internal_remove_component(id: String);
 }
 
+/// Instance rendered when the host does not pass one
+pub const DEFAULT_INSTANCE: u32 = 0;
+
+/// A registered component and its rendered instances. Every instance has its own VDOM, props and state
+struct RegisteredComponent {
+    create: fn() -> Box<dyn AnyComponent>,
+    instances: HashMap<u32, Renderer>,
+}
+
 thread_local! {
-    static COMPONENTS: Rc<RefCell<HashMap<String, Renderer>>> = Rc::new(RefCell::new(HashMap::new()));
+    static COMPONENTS: Rc<RefCell<HashMap<String, RegisteredComponent>>> = Rc::new(RefCell::new(HashMap::new()));
+}
+
+fn create_component<T>() -> Box<dyn AnyComponent>
+where
+    T: UIComponent + 'static,
+{
+    Box::new(ComponentInstance::<T> {
+        component_cache: None,
+        last_props: None,
+    })
 }
 
 pub fn remove_component(id: &Uuid) {
-    COMPONENTS.with(|value| {
-        value.borrow_mut().remove(&id.to_string());
-    });
+    let removed = COMPONENTS.with(|value| value.borrow_mut().remove(&id.to_string()));
+
+    // Dropped outside of the borrow, as it unmounts the instances of the component
+    drop(removed);
 
     internal_remove_component(id.to_string());
 }
@@ -35,31 +55,231 @@ where
 {
     info!("Registering component with id {}", id);
 
-    COMPONENTS.with(|value| {
+    // The manifest lists only the components registered in the entry point marked with #[plugin]
+    if let Some(manifest) = manifest::get_manifest() {
+        if !manifest.ui.components.contains(id) {
+            logging::adv_error(
+                format!(
+                    "Component {} is not declared in the plugin manifest. Register it in the #[plugin] entry point",
+                    id
+                )
+                .as_str(),
+                None,
+                false,
+            );
+        }
+    }
+
+    let replaced = COMPONENTS.with(|value| {
         value.borrow_mut().insert(
             id.to_string(),
-            Renderer::new(
-                *id,
-                Box::new(ComponentInstance::<T> {
-                    component_cache: None,
-                    last_props: None,
-                }),
-            ),
-        );
+            RegisteredComponent {
+                create: create_component::<T>,
+                instances: HashMap::new(),
+            },
+        )
     });
 
+    // Dropped outside of the borrow, as it unmounts the instances of the replaced component
+    drop(replaced);
+
     internal_register_component(id.to_string());
 }
 
+/// Renders the instance of the component, creating it on the first render.
+/// Returns the whole UI on the first render, later renders send only the changes
 #[wasm_bindgen]
-pub fn render_component(id: String, props: Option<String>) -> Option<String> {
-    info!("Rendering component with ID {}", id);
+pub fn render_component(id: String, props: Option<String>, instance: Option<u32>) -> Option<String> {
+    let instance = instance.unwrap_or(DEFAULT_INSTANCE);
+    info!("Rendering component with ID {} (instance {})", id, instance);
 
     COMPONENTS.with(|value| {
+        let mut components = value.borrow_mut();
+        let component = components
+            .get_mut(&id)
+            .expect("The component should be registered already");
+
+        let component_id = Uuid::parse_str(&id).expect("The component ID should be a valid UUID");
+        component
+            .instances
+            .entry(instance)
+            .or_insert_with(|| {
+                Renderer::new(
+                    InstanceId {
+                        component_id,
+                        instance,
+                    },
+                    (component.create)(),
+                )
+            })
+            .render(props)
+    })
+}
+
+/// Renders the component again with its last props. Used to recover a component showing its error fallback
+#[wasm_bindgen]
+pub fn retry_component(id: String, instance: Option<u32>) -> Option<String> {
+    info!("Retrying render of component with ID {}", id);
+
+    with_renderer(&id, instance, |renderer| renderer.retry()).flatten()
+}
+
+/// Invoked by the host once it shows the rendered component
+#[wasm_bindgen]
+pub fn mount_component(id: String, instance: Option<u32>) {
+    with_renderer(&id, instance, |renderer| renderer.mount());
+}
+
+/// Invoked by the host once it removes the component from the screen. The component stays registered
+#[wasm_bindgen]
+pub fn unmount_component(id: String, instance: Option<u32>) {
+    with_renderer(&id, instance, |renderer| renderer.unmount());
+}
+
+/// Invoked by the host once it no longer shows the instance, e.g. when a post of a feed is recycled.
+/// The instance is unmounted and dropped, the component stays registered
+#[wasm_bindgen]
+pub fn release_component_instance(id: String, instance: u32) {
+    let renderer = COMPONENTS.with(|value| {
         value
             .borrow_mut()
             .get_mut(&id)
-            .expect("The component should be registered already")
-            .render(props)
+            .and_then(|component| component.instances.remove(&instance))
+    });
+
+    // Dropped outside of the borrow, as it unmounts the component
+    drop(renderer);
+}
+
+/// Invoked by the host when the component scrolls in or out of the screen
+#[wasm_bindgen]
+pub fn set_component_visibility(id: String, visible: bool, instance: Option<u32>) {
+    with_renderer(&id, instance, |renderer| renderer.set_visible(visible));
+}
+
+/// Invoked by the host when the app moves to the foreground or the background. Accepts `"foreground"` or `"background"`
+#[wasm_bindgen]
+pub fn set_app_state(state: String) {
+    let state = match serde_json::from_value::<AppState>(serde_json::Value::String(state)) {
+        Ok(state) => state,
+        Err(e) => {
+            logging::adv_error(
+                format!("Failed to deserialize AppState -> {}", e).as_str(),
+                None,
+                false,
+            );
+            return;
+        }
+    };
+
+    let instances = COMPONENTS.with(|value| {
+        value
+            .borrow()
+            .iter()
+            .flat_map(|(id, component)| {
+                component
+                    .instances
+                    .keys()
+                    .map(move |instance| (id.clone(), *instance))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Instances released by the callbacks of the previous ones are skipped
+    for (id, instance) in instances {
+        if let Some(mut renderer) = take_renderer(&id, instance) {
+            renderer.app_state_changed(state);
+            restore_renderer(&id, instance, renderer);
+        }
+    }
+}
+
+/// Returns the result of the function for the rendered instance. `None` while the components are borrowed, e.g. by a render
+pub(crate) fn with_rendered_instance<T>(
+    id: &InstanceId,
+    function: impl FnOnce(&Renderer) -> Option<T>,
+) -> Option<T> {
+    COMPONENTS.with(|value| {
+        let components = value.try_borrow().ok()?;
+        let renderer = components
+            .get(&id.component_id.to_string())?
+            .instances
+            .get(&id.instance)?;
+        function(renderer)
+    })
+}
+
+/// Returns the instance whose rendered UI holds the listener id, searching all of them
+pub(crate) fn find_listener_owner(id: &str) -> Option<InstanceId> {
+    COMPONENTS.with(|value| {
+        value
+            .try_borrow()
+            .ok()?
+            .values()
+            .flat_map(|component| component.instances.values())
+            .find(|renderer| renderer.listener_path(id).is_some())
+            .map(|renderer| renderer.id())
+    })
+}
+
+/// Runs the function with the renderer taken out of the registry, so the lifecycle callbacks it runs
+/// can register, remove and release components
+fn with_renderer<T>(
+    id: &str,
+    instance: Option<u32>,
+    function: impl FnOnce(&mut Renderer) -> T,
+) -> Option<T> {
+    let instance = instance.unwrap_or(DEFAULT_INSTANCE);
+    let Some(mut renderer) = take_renderer(id, instance) else {
+        logging::adv_error(
+            format!(
+                "Instance {} of the component with ID {} was not rendered yet",
+                instance, id
+            )
+            .as_str(),
+            None,
+            false,
+        );
+        return None;
+    };
+
+    let result = function(&mut renderer);
+    restore_renderer(id, instance, renderer);
+    Some(result)
+}
+
+fn take_renderer(id: &str, instance: u32) -> Option<Renderer> {
+    COMPONENTS.with(|value| {
+        value
+            .borrow_mut()
+            .get_mut(id)
+            .and_then(|component| component.instances.remove(&instance))
     })
 }
+
+/// Puts the renderer back, unless its component was removed or the instance rendered again meanwhile
+fn restore_renderer(id: &str, instance: u32, renderer: Renderer) {
+    let rejected = COMPONENTS.with(|value| match value.borrow_mut().get_mut(id) {
+        Some(component) if !component.instances.contains_key(&instance) => {
+            component.instances.insert(instance, renderer);
+            None
+        }
+        _ => Some(renderer),
+    });
+
+    // Dropped outside of the borrow, as it unmounts the component
+    drop(rejected);
+}
+
+/// Renders a registered component again after its state changed
+pub(crate) fn rerender_component(id: &InstanceId) {
+    COMPONENTS.with(|value| {
+        if let Some(renderer) = value
+            .borrow_mut()
+            .get_mut(&id.component_id.to_string())
+            .and_then(|component| component.instances.get_mut(&id.instance))
+        {
+            renderer.rerender();
+        }
+    });
+}
@@ -1,21 +1,129 @@
-use super::{elements::UIElement, events::UIEvent};
-use crate::{error, logging, warn};
+use super::{
+    elements::{NativeElement, NativeElementType, Text, UIElement, View},
+    events::UIEvent,
+    hooks::{self, HookStore},
+    renderer::{release_element_listeners, share_listeners, InstanceId, RENDER_TARGET},
+};
+use crate::{
+    logging::{self, LogLevel, LogRecord},
+    utils::crypto::random_v4_uuid,
+    warn,
+};
 
-use serde::de::DeserializeOwned;
-use std::any::Any;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{any::Any, cell::RefCell, rc::Rc};
+use uuid::Uuid;
 
 pub trait AnyComponent: Any {
-    fn render(&mut self, props: Option<String>) -> Option<UIElement>;
+    fn render(&mut self, props: Option<String>) -> Result<UIElement, RenderError>;
+    fn fallback(&self, error: &RenderError) -> Option<UIElement>;
+    /// Returns whether the component must render with the props, while its state did not change
+    fn should_render(&self, props: &Option<String>) -> bool;
 
     fn mount(&mut self);
     fn unmount(&mut self);
+    fn visibility_changed(&mut self, visible: bool);
+    fn app_state_changed(&mut self, state: AppState);
 }
 
 pub trait UIComponent: DeserializeOwned {
     fn render(&mut self) -> Option<UIElement>;
 
+    /// Rendered instead of the component when its rendering fails. Returning `None` removes the component from the UI
+    fn fallback(error: &RenderError) -> Option<UIElement> {
+        Some(default_fallback(error))
+    }
+
+    /// Invoked before rendering while neither the state nor the used contexts changed. `self` holds the new props.
+    /// Returning `false` skips the render and the diff, so the previous UI is kept.
+    ///
+    /// Memoized components can return `self != previous`, as `#[ui_component]` derives `PartialEq`
+    fn should_render(&self, _previous: &Self) -> bool {
+        true
+    }
+
+    /// Invoked once the host shows the component
     fn mount(&mut self);
+    /// Invoked once the host removes the component. Its state is dropped afterwards
     fn unmount(&mut self);
+    /// Invoked before rendering with new props. `self` already holds the new props
+    fn update(&mut self, _previous: &Self) {}
+    /// Invoked when the component scrolls in or out of the screen, or is hidden by the host
+    fn visibility_changed(&mut self, _visible: bool) {}
+    /// Invoked when the app moves to the foreground or the background
+    fn app_state_changed(&mut self, _state: AppState) {}
+}
+
+/// State of the app running the plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AppState {
+    Foreground,
+    Background,
+}
+
+/// Reason why a component could not be rendered.
+/// Panics are not render errors, they crash the whole plugin as plugins are built with `panic = "abort"`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RenderError {
+    /// The props could not be deserialized into the component
+    InvalidProps { message: String },
+    /// The component rendered `None`
+    Empty,
+}
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::InvalidProps { message } => {
+                write!(f, "Invalid props were passed to the component: {}", message)
+            }
+            RenderError::Empty => write!(f, "Component did not render any element"),
+        }
+    }
+}
+impl std::error::Error for RenderError {}
+
+/// Fallback used by components which do not override [`UIComponent::fallback`]
+pub fn default_fallback(error: &RenderError) -> UIElement {
+    let mut children = vec![UIElement::Native(NativeElement::new(
+        NativeElementType::Text,
+        Text {
+            class_name: Some("text-foreground font-inter-bold".into()),
+            ..Default::default()
+        }
+        .into(),
+        Some(vec!["Something went wrong while rendering this component".into()]),
+        None,
+    ))
+    .into()];
+
+    if cfg!(debug_assertions) {
+        children.push(
+            UIElement::Native(NativeElement::new(
+                NativeElementType::Text,
+                Text {
+                    class_name: Some("text-muted-foreground".into()),
+                    ..Default::default()
+                }
+                .into(),
+                Some(vec![error.to_string().into()]),
+                None,
+            ))
+            .into(),
+        );
+    }
+
+    UIElement::Native(NativeElement::new(
+        NativeElementType::View,
+        View {
+            class_name: Some("p-2".into()),
+            ..Default::default()
+        }
+        .into(),
+        Some(children),
+        None,
+    ))
 }
 
 #[derive(Default)]
@@ -31,38 +139,30 @@ impl<T> ComponentInstance<T>
 where
     T: UIComponent,
 {
-    fn update_component(&mut self, props: Option<String>) {
+    fn update_component(&mut self, props: Option<String>) -> Result<(), RenderError> {
         // Props are the same no need to change
         if props == self.last_props && self.component_cache.is_some() {
-            return;
+            return Ok(());
         }
 
         self.last_props = props.clone();
 
-        match props {
-            Some(prps) => {
-                self.component_cache = match serde_json::from_str::<T>(prps.as_str()) {
-                    Ok(res) => Some(res),
-                    Err(e) => {
-                        error!("Invalid props were passed to the component: {:?}", e);
-
-                        None
+        // The component could be propless
+        let props = props.unwrap_or_else(|| String::from("{}"));
+        match serde_json::from_str::<T>(props.as_str()) {
+            Ok(component) => {
+                if let Some(previous) = self.component_cache.replace(component) {
+                    if let Some(component) = self.component_cache.as_mut() {
+                        component.update(&previous);
                     }
-                };
+                }
+                Ok(())
             }
-            None => {
-                // The component could be propless
-                self.component_cache = match serde_json::from_str::<T>("{}") {
-                    Ok(res) => Some(res),
-                    Err(e) => {
-                        logging::adv_fatal(
-                            format!("Invalid props were passed to the component: {:?}", e).as_str(),
-                            None,
-                        );
-
-                        None
-                    }
-                };
+            Err(e) => {
+                self.component_cache = None;
+                Err(RenderError::InvalidProps {
+                    message: e.to_string(),
+                })
             }
         }
     }
@@ -72,12 +172,31 @@ impl<T> AnyComponent for ComponentInstance<T>
 where
     T: UIComponent,
 {
-    fn render(&mut self, props: Option<String>) -> Option<UIElement> {
-        self.update_component(props);
+    fn render(&mut self, props: Option<String>) -> Result<UIElement, RenderError> {
+        self.update_component(props)?;
 
         match self.component_cache.as_mut() {
-            Some(comp) => comp.render(),
-            None => None,
+            Some(comp) => comp.render().ok_or(RenderError::Empty),
+            None => Err(RenderError::Empty),
+        }
+    }
+
+    fn fallback(&self, error: &RenderError) -> Option<UIElement> {
+        T::fallback(error)
+    }
+
+    fn should_render(&self, props: &Option<String>) -> bool {
+        let Some(component) = self.component_cache.as_ref() else {
+            return true;
+        };
+        if *props == self.last_props {
+            return component.should_render(component);
+        }
+
+        let props = props.as_deref().unwrap_or("{}");
+        match serde_json::from_str::<T>(props) {
+            Ok(next) => next.should_render(component),
+            Err(_) => true,
         }
     }
 
@@ -95,11 +214,221 @@ where
     fn unmount(&mut self) {
         match self.component_cache.as_mut() {
             Some(comp) => {
-                comp.mount();
+                comp.unmount();
             }
             None => {
                 warn!("Unmounting dead component");
             }
         }
     }
+
+    fn visibility_changed(&mut self, visible: bool) {
+        if let Some(comp) = self.component_cache.as_mut() {
+            comp.visibility_changed(visible);
+        }
+    }
+
+    fn app_state_changed(&mut self, state: AppState) {
+        if let Some(comp) = self.component_cache.as_mut() {
+            comp.app_state_changed(state);
+        }
+    }
+}
+
+/// Type erased nested component, so instances of different types can be stored together
+trait ChildComponent {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn mount(&mut self);
+    fn unmount(&mut self);
+    fn lifecycle_changed(&mut self, event: LifecycleEvent);
+}
+impl<T> ChildComponent for T
+where
+    T: UIComponent + 'static,
+{
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn mount(&mut self) {
+        UIComponent::mount(self);
+    }
+
+    fn unmount(&mut self) {
+        UIComponent::unmount(self);
+    }
+
+    fn lifecycle_changed(&mut self, event: LifecycleEvent) {
+        match event {
+            LifecycleEvent::Visibility(visible) => self.visibility_changed(visible),
+            LifecycleEvent::AppState(state) => self.app_state_changed(state),
+        }
+    }
+}
+
+/// Lifecycle changes propagated from a component to all of its nested components
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LifecycleEvent {
+    Visibility(bool),
+    AppState(AppState),
+}
+
+/// Notifies the nested components owned by the hook store and their descendants
+pub(crate) fn propagate_lifecycle(hooks: &RefCell<HookStore>, event: LifecycleEvent) {
+    for child in hooks.borrow_mut().children_mut() {
+        child.component.lifecycle_changed(event);
+        propagate_lifecycle(&child.hooks, event);
+    }
+}
+
+/// Mounts the nested components owned by the hook store and their descendants, which are not mounted yet.
+/// Invoked once the root component is mounted and after every render of a mounted root component
+pub(crate) fn mount_children(hooks: &RefCell<HookStore>) {
+    for child in hooks.borrow_mut().children_mut() {
+        // Descendants are mounted before their parent, like the nested components before the root
+        mount_children(&child.hooks);
+        if !child.mounted {
+            child.mounted = true;
+            let component = &mut child.component;
+            logging::with_component_scope(child.id.to_string(), || component.mount());
+        }
+    }
+}
+
+/// Renders the nested component, or its fallback if it renders `None`, like the root component does
+fn render_or_fallback<T: UIComponent>(component: &mut T) -> Option<UIElement> {
+    component.render().or_else(|| {
+        let error = RenderError::Empty;
+        logging::report_error(
+            LogRecord::new(
+                LogLevel::Error,
+                RENDER_TARGET,
+                format!(
+                    "Failed to render nested component {} -> {}",
+                    std::any::type_name::<T>(),
+                    error
+                ),
+            ),
+            None,
+            false,
+        );
+
+        T::fallback(&error)
+    })
+}
+
+/// Instance of a component nested in the UI of another component. Owned by the hook store of its parent
+pub(crate) struct ChildInstance {
+    id: Uuid,
+    component: Box<dyn ChildComponent>,
+    hooks: Rc<RefCell<HookStore>>,
+    /// Output of the last successful render, with its own listener ids. Reused when the component skips its render
+    cached: Option<Option<UIElement>>,
+    /// Set once the root component owning the instance is mounted, see [`mount_children`]
+    mounted: bool,
+}
+impl ChildInstance {
+    fn new<T: UIComponent + 'static>(component: T) -> ChildInstance {
+        ChildInstance {
+            id: random_v4_uuid(),
+            component: Box::new(component),
+            hooks: Rc::default(),
+            cached: None,
+            mounted: false,
+        }
+    }
+
+    fn set_cached(&mut self, cached: Option<Option<UIElement>>) {
+        if let Some(Some(element)) = std::mem::replace(&mut self.cached, cached) {
+            release_element_listeners(&element);
+        }
+    }
+
+    /// Returns a copy of the cached output, with the listeners registered under new ids, as the diff takes over its ids
+    fn reuse_cached(&self) -> Option<UIElement> {
+        self.cached.as_ref()?.as_ref().map(share_listeners)
+    }
+
+    fn render<T: UIComponent + 'static>(
+        &mut self,
+        renderer_id: InstanceId,
+        key: Option<String>,
+    ) -> Option<UIElement> {
+        let id = self.id;
+        let hooks = self.hooks.clone();
+        let component = self
+            .component
+            .as_any_mut()
+            .downcast_mut::<T>()
+            .expect("The child slot should contain a component of the same type");
+
+        let mut element = logging::with_component_scope(id.to_string(), || {
+            hooks::with_hooks(renderer_id, hooks, || render_or_fallback(component))
+        });
+
+        // The key of the component identifies its element when the children of the parent are reconciled
+        if let Some(element) = element.as_mut() {
+            let element_key = match element {
+                UIElement::Native(native) => &mut native.key,
+                UIElement::External(external) => &mut external.key,
+            };
+            if element_key.is_none() {
+                *element_key = key;
+            }
+        }
+
+        self.set_cached(Some(element.as_ref().map(share_listeners)));
+        element
+    }
+}
+impl Drop for ChildInstance {
+    /// The instance is dropped once its parent stops rendering it
+    fn drop(&mut self) {
+        if self.mounted {
+            self.component.unmount();
+        }
+        self.set_cached(None);
+    }
+}
+
+/// Renders a component nested in the UI of another component. Used by the `ui!` macro for non-native tags.
+///
+/// The instance keeps its state between renders of the parent. It is matched by its key, or by its position
+/// among the unkeyed components of the same type. New props replace the instance props only if they changed.
+/// The render is skipped if neither the state nor the used contexts changed and [`UIComponent::should_render`] returns `false`
+pub fn render_child<T>(props: T, key: Option<String>) -> Option<UIElement>
+where
+    T: UIComponent + PartialEq + 'static,
+{
+    let Some((renderer_id, slot)) = hooks::child_slot::<T>(key.clone()) else {
+        // Rendered outside of a component, so there is nothing to own the instance
+        let mut component = props;
+        return render_or_fallback(&mut component);
+    };
+
+    let (mut instance, skip) = match hooks::take_child(&slot) {
+        Some(mut instance) => {
+            let can_skip = instance.cached.is_some() && !instance.hooks.borrow().needs_render();
+            let mut skip = false;
+            if let Some(component) = instance.component.as_any_mut().downcast_mut::<T>() {
+                skip = can_skip && !props.should_render(component);
+                if *component != props {
+                    let previous = std::mem::replace(component, props);
+                    component.update(&previous);
+                }
+            }
+            (instance, skip)
+        }
+        None => (ChildInstance::new(props), false),
+    };
+
+    let element = if skip {
+        instance.reuse_cached()
+    } else {
+        instance.render::<T>(renderer_id, key)
+    };
+    hooks::return_child(slot, instance);
+
+    element
 }
@@ -0,0 +1,103 @@
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::hooks::{ancestor_providers_depth, next_hook, record_consumed_context};
+
+/// Version of a context which was not provided by any ancestor
+const MISSING_CONTEXT_VERSION: u64 = 0;
+
+struct ProvidedContext {
+    type_id: TypeId,
+    value: Rc<dyn Any>,
+    version: u64,
+}
+
+/// Value provided by a component, kept in its hook slot between renders
+struct ProviderSlot<T> {
+    value: Rc<T>,
+    /// Changes whenever the value changes, so memoized consumers know they must render
+    version: u64,
+}
+
+thread_local! {
+    /// Contexts provided by the components which are rendering, the innermost last
+    static PROVIDERS: RefCell<Vec<ProvidedContext>> = const { RefCell::new(Vec::new()) };
+    static NEXT_VERSION: Cell<u64> = const { Cell::new(MISSING_CONTEXT_VERSION + 1) };
+}
+
+pub(crate) fn providers_depth() -> usize {
+    PROVIDERS.with_borrow(|providers| providers.len())
+}
+
+/// Removes the contexts provided by a component once it finishes rendering
+pub(crate) fn truncate_providers(depth: usize) {
+    PROVIDERS.with_borrow_mut(|providers| providers.truncate(depth));
+}
+
+/// Returns the innermost context of the type among the first `depth` provided contexts
+fn find_provided(type_id: TypeId, depth: usize) -> Option<(Rc<dyn Any>, u64)> {
+    PROVIDERS.with_borrow(|providers| {
+        providers[..depth.min(providers.len())]
+            .iter()
+            .rev()
+            .find(|provided| provided.type_id == type_id)
+            .map(|provided| (provided.value.clone(), provided.version))
+    })
+}
+
+/// Returns the version of the context visible to the components nested in the rendering component
+pub(crate) fn context_version(type_id: TypeId) -> u64 {
+    find_provided(type_id, usize::MAX).map_or(MISSING_CONTEXT_VERSION, |(_, version)| version)
+}
+
+/// Provides the value to every component nested in the UI rendered by the calling component.
+/// Components nested deeper can override it by providing their own value of the same type.
+/// The calling component itself still sees the value provided by its ancestors.
+///
+/// This is a hook, so it must be called on every render. Nested components are rendered together with
+/// their ancestors, so consumers render again whenever the provider renders a changed value
+pub fn provide_context<T: PartialEq + 'static>(value: T) {
+    let (_, slot) = next_hook("provide_context", || RefCell::new(None::<ProviderSlot<T>>));
+    let mut slot = slot.borrow_mut();
+
+    // The previous value is kept if it did not change, so memoized consumers can skip their render
+    if slot.as_ref().is_none_or(|provided| *provided.value != value) {
+        let version = NEXT_VERSION.get();
+        NEXT_VERSION.set(version + 1);
+
+        *slot = Some(ProviderSlot {
+            value: Rc::new(value),
+            version,
+        });
+    }
+
+    if let Some(provided) = slot.as_ref() {
+        PROVIDERS.with_borrow_mut(|providers| {
+            providers.push(ProvidedContext {
+                type_id: TypeId::of::<T>(),
+                value: provided.value.clone(),
+                version: provided.version,
+            })
+        });
+    }
+}
+
+/// Returns the value provided by the nearest ancestor with [`provide_context`], if any
+pub fn use_context<T: 'static>() -> Option<Rc<T>> {
+    let type_id = TypeId::of::<T>();
+    // Contexts provided by the rendering component itself are visible only to its descendants
+    let depth = ancestor_providers_depth().unwrap_or(usize::MAX);
+    match find_provided(type_id, depth) {
+        Some((value, version)) => {
+            record_consumed_context(type_id, version);
+            value.downcast::<T>().ok()
+        }
+        None => {
+            record_consumed_context(type_id, MISSING_CONTEXT_VERSION);
+            None
+        }
+    }
+}
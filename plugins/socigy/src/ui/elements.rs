@@ -1,6 +1,9 @@
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    cell::OnceCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 use serde::{Deserialize, Serialize};
@@ -8,9 +11,10 @@ use serde_json::Value;
 use socigy_macros::{ui_component, UIProps};
 use uuid::Uuid;
 
+use super::{list::ListData, style::Style};
 use crate::logging;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "PascalCase")]
 pub enum NativeElementType {
     Fragment,
@@ -31,10 +35,46 @@ pub enum NativeElementType {
 pub enum NativeElementProps {
     View(View),
     Text(Text),
+    Pressable(Pressable),
+    TouchableOpacity(TouchableOpacity),
+    ScrollView(ScrollView),
     Image(Image),
+    TextInput(TextInput),
     FlatList(FlatList),
+    SafeAreaView(SafeAreaView),
+    FlashList(FlashList),
 }
 
+/// Implements the conversions used by `ui!` to pass the props of a native element
+macro_rules! native_props {
+    ($($name:ident),*) => {
+        $(
+            impl From<$name> for NativeElementProps {
+                fn from(value: $name) -> Self {
+                    NativeElementProps::$name(value)
+                }
+            }
+            impl From<$name> for Option<NativeElementProps> {
+                fn from(value: $name) -> Self {
+                    Some(value.into())
+                }
+            }
+        )*
+    };
+}
+native_props!(
+    View,
+    Text,
+    Pressable,
+    TouchableOpacity,
+    ScrollView,
+    Image,
+    TextInput,
+    FlatList,
+    SafeAreaView,
+    FlashList
+);
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum PropStr {
@@ -66,73 +106,368 @@ impl From<UIElementChildren> for PropStr {
     }
 }
 
-#[derive(Clone, Debug)]
+/// Whether the element can be the target of touch events
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PointerEvents {
+    Auto,
+    None,
+    BoxNone,
+    BoxOnly,
+}
+
+#[derive(Clone, Debug, Default)]
 #[ui_component]
 pub struct View {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub class_name: Option<PropStr>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub style: Option<PropStr>,
+    pub style: Option<Box<Style>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pointer_events: Option<PointerEvents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
+}
+
+/// Where the text is truncated once it exceeds `number_of_lines`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EllipsizeMode {
+    Head,
+    Middle,
+    Tail,
+    Clip,
+}
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct Text {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    /// Truncates the text after the number of lines. Integer props are `i32`, so `number_of_lines={2}` compiles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_lines: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ellipsize_mode: Option<EllipsizeMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selectable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct Pressable {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    /// Milliseconds after which a press becomes a long press
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_long_press: Option<i32>,
+    /// Distance in points outside of the element in which a press is still detected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_slop: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
-impl From<View> for NativeElementProps {
-    fn from(value: View) -> Self {
-        NativeElementProps::View(value)
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct TouchableOpacity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    /// Opacity of the element while it is pressed, from `0.0` to `1.0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_opacity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_long_press: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
+}
+
+/// Whether a tap outside of a focused text input dismisses the keyboard
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyboardShouldPersistTaps {
+    Always,
+    Never,
+    Handled,
+}
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct ScrollView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    /// Classes of the inner container wrapping the scrolled content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_container_class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounces: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_vertical_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_horizontal_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard_should_persist_taps: Option<KeyboardShouldPersistTaps>,
+    /// Minimal number of milliseconds between two scroll events sent to the plugin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_event_throttle: Option<i32>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
+}
+
+/// Image shown by the [`Image`] element
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSource {
+    pub uri: PropStr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+}
+impl From<PropStr> for ImageSource {
+    fn from(value: PropStr) -> Self {
+        ImageSource {
+            uri: value,
+            width: None,
+            height: None,
+        }
+    }
+}
+impl From<String> for ImageSource {
+    fn from(value: String) -> Self {
+        PropStr::from(value).into()
     }
 }
-impl From<View> for Option<NativeElementProps> {
-    fn from(value: View) -> Self {
-        Some(value.into())
+impl From<&'static str> for ImageSource {
+    fn from(value: &'static str) -> Self {
+        PropStr::from(value).into()
     }
 }
 
-#[derive(Clone, Debug)]
+/// How the image is resized when its size differs from the size of the element
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResizeMode {
+    Cover,
+    Contain,
+    Stretch,
+    Repeat,
+    Center,
+}
+
+#[derive(Clone, Debug, Default)]
 #[ui_component]
 pub struct Image {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    /// Accepts the URI directly, e.g. `source="https://..."`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<ImageSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_mode: Option<ResizeMode>,
+    /// Description read by screen readers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
-impl From<Image> for NativeElementProps {
-    fn from(value: Image) -> Self {
-        NativeElementProps::Image(value)
-    }
+
+/// Keyboard shown while the text input is focused
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyboardType {
+    Default,
+    NumberPad,
+    DecimalPad,
+    Numeric,
+    EmailAddress,
+    PhonePad,
+    Url,
 }
-impl From<Image> for Option<NativeElementProps> {
-    fn from(value: Image) -> Self {
-        Some(value.into())
-    }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoCapitalize {
+    None,
+    Sentences,
+    Words,
+    Characters,
+}
+
+/// Label of the return key of the keyboard
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReturnKeyType {
+    Done,
+    Go,
+    Next,
+    Search,
+    Send,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 #[ui_component]
-pub struct Text {
+pub struct TextInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    /// Controlled value of the input. Leave unset and use `default_value` for an uncontrolled input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder_text_color: Option<PropStr>,
+    /// Hides the entered text, e.g. for passwords
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secure_text_entry: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard_type: Option<KeyboardType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_capitalize: Option<AutoCapitalize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_correct: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_focus: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_key_type: Option<ReturnKeyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<PropStr>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
-impl From<Text> for NativeElementProps {
-    fn from(value: Text) -> Self {
-        NativeElementProps::Text(value)
-    }
+
+/// Edge of the screen which the [`SafeAreaView`] keeps its content away from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SafeAreaEdge {
+    Top,
+    Right,
+    Bottom,
+    Left,
 }
-impl From<Text> for Option<NativeElementProps> {
-    fn from(value: Text) -> Self {
-        Some(value.into())
-    }
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct SafeAreaView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    /// Edges which get the safe area insets. All of them if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edges: Option<Vec<SafeAreaEdge>>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 #[ui_component]
 pub struct FlatList {
+    /// Items of the list, created with `use_list`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<ListData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_container_class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inverted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_columns: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_num_to_render: Option<i32>,
+    /// Distance from the end, in visible lengths of the list, at which `on_end_reached` is invoked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_end_reached_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_vertical_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_horizontal_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard_should_persist_taps: Option<KeyboardShouldPersistTaps>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
-impl From<FlatList> for NativeElementProps {
-    fn from(value: FlatList) -> Self {
-        NativeElementProps::FlatList(value)
-    }
-}
-impl From<FlatList> for Option<NativeElementProps> {
-    fn from(value: FlatList) -> Self {
-        Some(value.into())
-    }
+
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct FlashList {
+    /// Items of the list, created with `use_list`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<ListData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Box<Style>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_container_class_name: Option<PropStr>,
+    /// Expected size of an item in points, used to lay out the items which were not measured yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_item_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inverted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_columns: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_end_reached_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_vertical_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shows_horizontal_scroll_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard_should_persist_taps: Option<KeyboardShouldPersistTaps>,
+    #[serde(rename = "testID", skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<PropStr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -166,6 +501,9 @@ pub struct ExternalElement {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) events: Option<HashMap<String, HashSet<String>>>,
 
+    #[serde(skip)]
+    pub(crate) content_hash: OnceCell<u64>,
+
     #[serde(rename = "type", serialize_with = "serialize_external_type")]
     pub(crate) _phantom_type: (),
 }
@@ -181,8 +519,9 @@ where
 pub struct NativeElement {
     #[serde(rename = "type")]
     pub native_type: NativeElementType,
+    /// Boxed, so the children holding elements stay small
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub props: Option<NativeElementProps>,
+    pub props: Option<Box<NativeElementProps>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "serialize_children")]
     pub children: Option<Vec<UIElementChildren>>,
@@ -191,6 +530,9 @@ pub struct NativeElement {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) events: Option<HashMap<String, HashSet<String>>>,
+
+    #[serde(skip)]
+    pub(crate) content_hash: OnceCell<u64>,
 }
 
 #[derive(Serialize, Clone, Deserialize, PartialEq)]
@@ -215,35 +557,14 @@ where
 {
     use serde::ser::SerializeSeq;
 
+    warn_about_keys(elements.iter().flatten());
+
     let mut seq =
         serializer.serialize_seq(Some(elements.iter().filter(|e| e.is_some()).count()))?;
-
-    let mut key_not_found = false;
-    let mut key_values = HashSet::<&String>::new();
     for element in elements.iter().flatten() {
-        let key_result = enforce_key(element);
-        match key_result {
-            Some(key) => {
-                if !key_values.insert(key) {
-                    logging::adv_error(
-                        "Conflicting element key has been found. Please assign unique keys",
-                        None,
-                        false,
-                    );
-                }
-            }
-            None => {
-                key_not_found = true;
-            }
-        }
-
         seq.serialize_element(element)?;
     }
 
-    if key_not_found {
-        logging::adv_error("Not all elements in list have key assigned. For better performance please assign unique keys", None, false);
-    }
-
     seq.end()
 }
 
@@ -256,42 +577,101 @@ where
 {
     use serde::ser::SerializeSeq;
 
-    let mut seq = serializer.serialize_seq(Some(elements.iter().count()))?;
+    warn_about_keys(elements.iter());
 
+    let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+    for element in elements {
+        seq.serialize_element(element)?;
+    }
+
+    seq.end()
+}
+
+fn enforce_key(child: &UIElement) -> &Option<String> {
+    match child {
+        UIElement::External(external) => &external.key,
+        UIElement::Native(native) => &native.key,
+    }
+}
+
+/// A child as seen by the host, after the nested lists are flattened and adjacent strings merged
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FlatChild<'a> {
+    Element(&'a UIElement),
+    Text(String),
+    Empty,
+}
+impl FlatChild<'_> {
+    pub(crate) fn key(&self) -> Option<&String> {
+        match self {
+            FlatChild::Element(element) => enforce_key(element).as_ref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_children(&self) -> UIElementChildren {
+        match self {
+            FlatChild::Element(element) => UIElementChildren::Element((*element).clone()),
+            FlatChild::Text(text) => UIElementChildren::String(text.clone()),
+            FlatChild::Empty => UIElementChildren::None,
+        }
+    }
+}
+impl Serialize for FlatChild<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            FlatChild::Element(element) => element.serialize(serializer),
+            FlatChild::Text(text) => serializer.serialize_str(text),
+            FlatChild::Empty => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Flattens the children the same way they are serialized, so indexes used by the diff match the host tree
+pub(crate) fn flatten_children(children: &[UIElementChildren]) -> Vec<FlatChild<'_>> {
+    let mut flat = Vec::with_capacity(children.len());
+    for child in children {
+        match child {
+            UIElementChildren::OptionalElements(nested) => {
+                flat.extend(nested.iter().flatten().map(FlatChild::Element));
+            }
+            UIElementChildren::Elements(nested) => {
+                flat.extend(nested.iter().map(FlatChild::Element));
+            }
+            UIElementChildren::Element(element) => flat.push(FlatChild::Element(element)),
+            UIElementChildren::String(text) => match flat.last_mut() {
+                Some(FlatChild::Text(previous)) => previous.push_str(text),
+                _ => flat.push(FlatChild::Text(text.clone())),
+            },
+            UIElementChildren::None => flat.push(FlatChild::Empty),
+        }
+    }
+
+    flat
+}
+
+fn warn_about_keys<'a>(elements: impl Iterator<Item = &'a UIElement>) {
     let mut key_not_found = false;
     let mut key_values = HashSet::<&String>::new();
-    for element in elements.iter() {
-        let key_result = enforce_key(element);
-        match key_result {
+    for element in elements {
+        match enforce_key(element) {
             Some(key) => {
                 if !key_values.insert(key) {
-                    logging::adv_error(
-                        "Conflicting element key has been found. Please assign unique keys",
-                        None,
-                        false,
-                    );
+                    logging::adv_error(format!("Conflicting element key has been found '{}'. Please assign unique keys", key).as_str(), None, false);
                 }
             }
             None => {
                 key_not_found = true;
             }
         }
-
-        seq.serialize_element(element)?;
     }
 
     if key_not_found {
         logging::adv_error("Not all elements in list have key assigned. For better performance please assign unique keys", None, false);
     }
-
-    seq.end()
-}
-
-fn enforce_key(child: &UIElement) -> &Option<String> {
-    match child {
-        UIElement::External(external) => &external.key,
-        UIElement::Native(native) => &native.key,
-    }
 }
 
 fn serialize_children<S>(
@@ -303,81 +683,21 @@ where
 {
     use serde::ser::SerializeSeq;
 
-    let mut seq = serializer.serialize_seq(Some(children.as_ref().map_or(0, |v| v.len())))?;
-
-    if let Some(children_vec) = children {
-        let mut child_iter = children_vec.into_iter().peekable();
-        let mut current_str = String::new();
-
-        while let Some(child) = child_iter.next() {
-            match child {
-                UIElementChildren::OptionalElements(nested) => {
-                    let mut key_not_found = false;
-                    let mut key_values = HashSet::<&String>::new();
-                    for nested_child in nested.iter().flatten() {
-                        let key_result = enforce_key(nested_child);
-                        match key_result {
-                            Some(key) => {
-                                if !key_values.insert(key) {
-                                    logging::adv_error(format!("Conflicting element key has been found '{}'. Please assign unique keys", key).as_str(), None, false);
-                                }
-                            }
-                            None => {
-                                key_not_found = true;
-                            }
-                        }
-
-                        seq.serialize_element(nested_child)?;
-                    }
-
-                    if key_not_found {
-                        logging::adv_error("Not all elements in list have key assigned. For better performance please assign unique keys", None, false);
-                    }
-                }
-                UIElementChildren::Elements(nested) => {
-                    let mut key_not_found = false;
-                    let mut key_values = HashSet::<&String>::new();
-                    for nested_child in nested.iter() {
-                        let key_result = enforce_key(nested_child);
-                        match key_result {
-                            Some(key) => {
-                                if !key_values.insert(key) {
-                                    logging::adv_error("Conflicting element key has been found. Please assign unique keys", None, false);
-                                }
-                            }
-                            None => {
-                                key_not_found = true;
-                            }
-                        }
-
-                        seq.serialize_element(nested_child)?;
-                    }
-
-                    if key_not_found {
-                        logging::adv_error("Not all elements in list have key assigned. For better performance please assign unique keys", None, false);
-                    }
-                }
-                UIElementChildren::String(str) => match child_iter.peek() {
-                    Some(UIElementChildren::String(next_str)) => {
-                        current_str.push_str(&str);
-                        current_str.push_str(next_str);
-                    }
-                    _ => {
-                        if !current_str.is_empty() {
-                            seq.serialize_element(&current_str)?;
-                            current_str.clear();
-                        } else {
-                            seq.serialize_element(&str)?;
-                        }
-                    }
-                },
-                _ => {
-                    seq.serialize_element(&child)?;
-                }
-            }
+    let children = children.as_deref().unwrap_or_default();
+    for child in children {
+        match child {
+            UIElementChildren::OptionalElements(nested) => warn_about_keys(nested.iter().flatten()),
+            UIElementChildren::Elements(nested) => warn_about_keys(nested.iter()),
+            _ => {}
         }
     }
 
+    let flat = flatten_children(children);
+    let mut seq = serializer.serialize_seq(Some(flat.len()))?;
+    for child in &flat {
+        seq.serialize_element(child)?;
+    }
+
     seq.end()
 }
 
@@ -389,6 +709,7 @@ impl Default for ExternalElement {
             props: Default::default(),
             children: Default::default(),
             events: Default::default(),
+            content_hash: OnceCell::new(),
             _phantom_type: (),
         }
     }
@@ -400,18 +721,133 @@ impl Default for NativeElement {
             props: Default::default(),
             children: Default::default(),
             events: Default::default(),
+            content_hash: OnceCell::new(),
             key: Default::default(),
         }
     }
 }
 
+/// Listener ids change on every render, so events are compared only by their names and listener counts
+fn event_counts(events: &Option<HashMap<String, HashSet<String>>>) -> BTreeMap<&String, usize> {
+    events
+        .iter()
+        .flatten()
+        .filter(|(_, ids)| !ids.is_empty())
+        .map(|(name, ids)| (name, ids.len()))
+        .collect()
+}
+
+/// Feeds everything written into it to the hasher, so values can be hashed through their serialized form
+struct HashWriter<'a>(&'a mut DefaultHasher);
+impl std::io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn hash_serialized<T: Serialize>(value: &T, hasher: &mut DefaultHasher) {
+    static UNSERIALIZABLE: AtomicU64 = AtomicU64::new(0);
+
+    if serde_json::to_writer(HashWriter(hasher), value).is_err() {
+        // Unserializable values can not be compared, so the subtree is always treated as changed
+        hasher.write_u64(UNSERIALIZABLE.fetch_add(1, Ordering::Relaxed));
+    }
+}
+
+fn hash_children(children: &Option<Vec<UIElementChildren>>, hasher: &mut DefaultHasher) {
+    let Some(children) = children else {
+        hasher.write_u8(0);
+        return;
+    };
+
+    hasher.write_usize(children.len());
+    for child in children {
+        match child {
+            UIElementChildren::None => hasher.write_u8(1),
+            UIElementChildren::String(str) => {
+                hasher.write_u8(2);
+                str.hash(hasher);
+            }
+            UIElementChildren::Element(element) => {
+                hasher.write_u8(3);
+                hasher.write_u64(element.content_hash());
+            }
+            UIElementChildren::OptionalElements(elements) => {
+                hasher.write_u8(4);
+                hasher.write_usize(elements.len());
+                for element in elements {
+                    hasher.write_u64(element.as_ref().map_or(0, |e| e.content_hash()));
+                }
+            }
+            UIElementChildren::Elements(elements) => {
+                hasher.write_u8(5);
+                hasher.write_usize(elements.len());
+                for element in elements {
+                    hasher.write_u64(element.content_hash());
+                }
+            }
+        }
+    }
+}
+
+impl UIElement {
+    /// Hash of the whole subtree, computed once and cached. Equal hashes mean the subtrees render the same,
+    /// so the element must not be changed after the hash is computed
+    pub fn content_hash(&self) -> u64 {
+        match self {
+            UIElement::Native(native) => native.content_hash(),
+            UIElement::External(external) => external.content_hash(),
+        }
+    }
+}
+
+impl NativeElement {
+    pub fn content_hash(&self) -> u64 {
+        *self.content_hash.get_or_init(|| {
+            let mut hasher = DefaultHasher::new();
+            hasher.write_u8(0);
+            self.native_type.hash(&mut hasher);
+            self.key.hash(&mut hasher);
+            hash_serialized(&self.props, &mut hasher);
+            event_counts(&self.events).hash(&mut hasher);
+            hash_children(&self.children, &mut hasher);
+
+            hasher.finish()
+        })
+    }
+}
+
+impl ExternalElement {
+    pub fn content_hash(&self) -> u64 {
+        *self.content_hash.get_or_init(|| {
+            let mut hasher = DefaultHasher::new();
+            hasher.write_u8(1);
+            self.id.hash(&mut hasher);
+            self.key.hash(&mut hasher);
+            // Sorted, as the order of HashMap entries is not stable
+            let props: Option<BTreeMap<&String, &Value>> =
+                self.props.as_ref().map(|props| props.iter().collect());
+            hash_serialized(&props, &mut hasher);
+            event_counts(&self.events).hash(&mut hasher);
+            hash_children(&self.children, &mut hasher);
+
+            hasher.finish()
+        })
+    }
+}
+
 impl PartialEq for ExternalElement {
     fn eq(&self, other: &Self) -> bool {
         self.key == other.key
             && self.id == other.id
-            && self.children.is_some() == other.children.is_some()
-            && self.children.as_ref().map(|c| c.len()) == other.children.as_ref().map(|c| c.len())
-            && self.props.is_some() == other.props.is_some()
+            && self.props == other.props
+            && event_counts(&self.events) == event_counts(&other.events)
+            && self.children == other.children
     }
 }
 
@@ -419,10 +855,9 @@ impl PartialEq for NativeElement {
     fn eq(&self, other: &Self) -> bool {
         self.key == other.key
             && self.native_type == other.native_type
-            && self.children.is_some() == other.children.is_some()
-            && self.children.as_ref().map(|c| c.len()) == other.children.as_ref().map(|c| c.len())
-            && self.props.is_some() == other.props.is_some()
-            && self.props.as_ref().map(|_| true) == other.props.as_ref().map(|_| true)
+            && self.props == other.props
+            && event_counts(&self.events) == event_counts(&other.events)
+            && self.children == other.children
     }
 }
 
@@ -435,9 +870,10 @@ impl NativeElement {
     ) -> NativeElement {
         NativeElement {
             native_type: native_type,
-            props: props,
+            props: props.map(Box::new),
             children: children,
             events: None,
+            content_hash: OnceCell::new(),
             key: key,
         }
     }
@@ -454,6 +890,7 @@ impl ExternalElement {
             props: props,
             children: children,
             events: None,
+            content_hash: OnceCell::new(),
             key: key,
             _phantom_type: (),
         }
@@ -484,6 +921,7 @@ impl From<UIElementChildren> for UIElement {
                 children: vec![UIElementChildren::String(str)].into(),
                 key: None,
                 events: None,
+                content_hash: OnceCell::new(),
             }),
             UIElementChildren::Elements(elements) => UIElement::Native(NativeElement {
                 native_type: NativeElementType::Fragment,
@@ -491,6 +929,7 @@ impl From<UIElementChildren> for UIElement {
                 children: Some(elements.into_iter().map(|e| e.into()).collect()),
                 key: None,
                 events: None,
+                content_hash: OnceCell::new(),
             }),
             UIElementChildren::OptionalElements(elements) => UIElement::Native(NativeElement {
                 native_type: NativeElementType::Fragment,
@@ -498,6 +937,7 @@ impl From<UIElementChildren> for UIElement {
                 children: Some(elements.into_iter().map(|e| e.into()).collect()),
                 key: None,
                 events: None,
+                content_hash: OnceCell::new(),
             }),
             UIElementChildren::None => UIElement::Native(NativeElement {
                 native_type: NativeElementType::Fragment,
@@ -505,6 +945,7 @@ impl From<UIElementChildren> for UIElement {
                 children: None,
                 key: None,
                 events: None,
+                content_hash: OnceCell::new(),
             }),
         }
     }
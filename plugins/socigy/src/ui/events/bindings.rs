@@ -1,26 +1,43 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::logging;
+use crate::{logging, utils::crypto::random_v4_uuid_str};
 
-use super::UIEvent;
+use super::{propagation::dispatch_event, EventType, UIEvent};
+use crate::ui::{
+    bindings::find_listener_owner,
+    list::{find_listener_list, list_renderer_id},
+    renderer::{mark_dirty, InstanceId},
+};
 
-#[wasm_bindgen(js_namespace = ["socigy", "ui", "events"])]
-extern "C" {
-    #[wasm_bindgen(js_name = "addEventListener")]
-    fn internal_add_event_listener(id: String, callback: JsValue);
+/// Listener closure, shared by all ids registered for it, e.g. by the cached UI of a memoized component
+pub(crate) type SharedListener = Rc<RefCell<Box<dyn FnMut(&UIEvent)>>>;
 
-    #[wasm_bindgen(js_name = "removeEventListener")]
-    fn remove_event_listener(id: String);
+/// Rendered tree holding a listener, the only one searched for the element of the listener
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ListenerOwner {
+    Instance(InstanceId),
+    /// Shown items of the virtualized list with the id
+    List(String),
 }
 
 thread_local! {
-    pub(crate) static REGISTERED_EVENTS: Rc<RefCell<HashMap<String, Box<dyn FnMut(&UIEvent)>>>> = Rc::new(RefCell::new(HashMap::new()));
+    pub(crate) static REGISTERED_EVENTS: Rc<RefCell<HashMap<String, SharedListener>>> = Rc::new(RefCell::new(HashMap::new()));
+    /// Owners of the listeners registered while their tree rendered, by the listener id
+    static LISTENER_OWNERS: RefCell<HashMap<String, ListenerOwner>> = RefCell::new(HashMap::new());
+    /// Tree which is rendering, the owner of the listeners registered meanwhile
+    static RENDERING_OWNER: RefCell<Option<ListenerOwner>> = const { RefCell::new(None) };
+    /// Listeners released while an event was being dispatched, freed once the dispatch finishes
+    static PENDING_RELEASES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    /// Texts the user entered into the text inputs since the last frame, by the id of their `onChangeText` listener
+    pub(crate) static ENTERED_TEXTS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
 }
 
+/// Dispatches the event the host sent to the listener, see [`dispatch_event`].
+/// Returns `true` if a listener prevented the default behavior of the host
 #[wasm_bindgen]
-pub fn invoke_ui_event(id: String, event: String) {
+pub fn invoke_ui_event(id: String, event: String) -> bool {
     let json_res = match serde_json::from_str::<UIEvent>(event.as_str()) {
         Ok(res) => res,
         Err(e) => {
@@ -29,13 +46,271 @@ pub fn invoke_ui_event(id: String, event: String) {
                 None,
                 false,
             );
-            return;
+            return false;
         }
     };
 
-    REGISTERED_EVENTS.with(move |events| {
-        if let Some(listener) = events.borrow_mut().get_mut(&id) {
-            listener(&json_res);
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("ui_event", listener_id = %id).entered();
+
+    dispatch_event(&id, &json_res);
+    if let EventType::OnChangeText(event) = &json_res.event_type {
+        record_entered_text(&id, &event.text);
+    }
+
+    release_listeners(&PENDING_RELEASES.take());
+    json_res.is_default_prevented()
+}
+
+/// Invokes the listener registered under the id, if any.
+/// The registry is not borrowed while the listener runs, so it can register and release listeners
+pub(crate) fn invoke_listener(id: &str, event: &UIEvent) {
+    let listener = REGISTERED_EVENTS.with(|events| events.borrow().get(id).cloned());
+    if let Some(listener) = listener {
+        (listener.borrow_mut())(event);
+    }
+}
+
+/// Returns the listener closures registered under the ids, so they can be invoked once the registry is not borrowed
+pub(crate) fn registered_listeners<'a>(
+    ids: impl IntoIterator<Item = &'a String>,
+) -> Vec<SharedListener> {
+    REGISTERED_EVENTS.with(|events| {
+        let events = events.borrow();
+        ids.into_iter()
+            .filter_map(|id| events.get(id).cloned())
+            .collect()
+    })
+}
+
+/// Renders the component owning the text input on the next frame, so the diff can send the value of a controlled input
+/// which rejected the entered text. The input keeps showing the entered text otherwise, as its value did not change
+fn record_entered_text(id: &str, text: &str) {
+    ENTERED_TEXTS.with_borrow_mut(|texts| texts.insert(id.to_string(), text.to_string()));
+    if let Some(renderer_id) = listener_renderer_id(id) {
+        mark_dirty(renderer_id);
+    }
+}
+
+/// Returns the instance rendering the listener, directly or in an item of its list
+fn listener_renderer_id(id: &str) -> Option<InstanceId> {
+    match listener_owner(id)? {
+        ListenerOwner::Instance(instance) => Some(instance),
+        ListenerOwner::List(list_id) => list_renderer_id(&list_id),
+    }
+}
+
+/// Returns the text entered into the input with the `onChangeText` listener since the last frame
+pub(crate) fn take_entered_text(id: &str) -> Option<String> {
+    ENTERED_TEXTS.with_borrow_mut(|texts| texts.remove(id))
+}
+
+pub(crate) fn has_entered_texts() -> bool {
+    ENTERED_TEXTS.with_borrow(|texts| !texts.is_empty())
+}
+
+/// Whether a text was entered since the last frame into an input rendered by the instance
+pub(crate) fn has_entered_texts_for(renderer_id: InstanceId) -> bool {
+    let ids = ENTERED_TEXTS.with_borrow(|texts| texts.keys().cloned().collect::<Vec<_>>());
+    ids.iter()
+        .any(|id| listener_renderer_id(id) == Some(renderer_id))
+}
+
+/// Forgets the entered texts once the frame rendering them finished
+pub(crate) fn clear_entered_texts() {
+    ENTERED_TEXTS.with_borrow_mut(|texts| texts.clear());
+}
+
+/// Makes the tree the owner of the listeners registered inside of the function, e.g. while it renders
+pub(crate) fn with_listener_owner<T>(owner: ListenerOwner, function: impl FnOnce() -> T) -> T {
+    /// Restores the outer owner even if the render panics
+    struct OwnerGuard(Option<ListenerOwner>);
+    impl Drop for OwnerGuard {
+        fn drop(&mut self) {
+            RENDERING_OWNER.set(self.0.take());
+        }
+    }
+
+    let _guard = OwnerGuard(RENDERING_OWNER.replace(Some(owner)));
+    function()
+}
+
+/// Registers the listener closure under a new id, owned by the rendering tree
+pub(crate) fn register_listener(listener: Box<dyn FnMut(&UIEvent)>) -> String {
+    let id = random_v4_uuid_str();
+    REGISTERED_EVENTS.with(|events| {
+        events
+            .borrow_mut()
+            .insert(id.clone(), Rc::new(RefCell::new(listener)))
+    });
+
+    if let Some(owner) = RENDERING_OWNER.with_borrow(|owner| owner.clone()) {
+        LISTENER_OWNERS.with_borrow_mut(|owners| owners.insert(id.clone(), owner));
+    }
+    id
+}
+
+/// Returns the tree holding the listener. Listeners registered outside of a render, e.g. by an element created
+/// in an event handler, are searched for in all rendered trees
+pub(crate) fn listener_owner(id: &str) -> Option<ListenerOwner> {
+    LISTENER_OWNERS
+        .with_borrow(|owners| owners.get(id).cloned())
+        .or_else(|| find_listener_owner(id).map(ListenerOwner::Instance))
+        .or_else(|| find_listener_list(id).map(ListenerOwner::List))
+}
+
+/// Moves the listener closures from the new ids (key) to the old ids (value) already known to the host
+pub(crate) fn transfer_listeners(renamed: &HashMap<String, String>) {
+    if renamed.is_empty() {
+        return;
+    }
+
+    REGISTERED_EVENTS.with(|events| {
+        let mut events = events.borrow_mut();
+        for (new_id, old_id) in renamed {
+            if let Some(listener) = events.remove(new_id) {
+                events.insert(old_id.clone(), listener);
+            }
         }
     });
+    LISTENER_OWNERS.with_borrow_mut(|owners| {
+        for (new_id, old_id) in renamed {
+            if let Some(owner) = owners.remove(new_id) {
+                owners.insert(old_id.clone(), owner);
+            }
+        }
+    });
+}
+
+/// Registers the listener under a new id as well. Returns `None` if the listener is not registered
+pub(crate) fn alias_listener(id: &str) -> Option<String> {
+    let alias = REGISTERED_EVENTS.with(|events| {
+        let mut events = events.borrow_mut();
+        let listener = events.get(id)?.clone();
+
+        let alias = random_v4_uuid_str();
+        events.insert(alias.clone(), listener);
+        Some(alias)
+    })?;
+
+    LISTENER_OWNERS.with_borrow_mut(|owners| {
+        if let Some(owner) = owners.get(id).cloned() {
+            owners.insert(alias.clone(), owner);
+        }
+    });
+    Some(alias)
+}
+
+/// Frees the listener closures. Releases requested while an event is dispatched are deferred until it finishes.
+/// The host is not notified, it forgets the ids with the `RemoveEventListener` changes and the elements it removes
+pub(crate) fn release_listeners(ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+
+    let _ = REGISTERED_EVENTS.try_with(|events| match events.try_borrow_mut() {
+        Ok(mut events) => {
+            LISTENER_OWNERS.with_borrow_mut(|owners| {
+                for id in ids {
+                    events.remove(id);
+                    owners.remove(id);
+                }
+            });
+        }
+        Err(_) => PENDING_RELEASES.with_borrow_mut(|pending| pending.extend_from_slice(ids)),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::ui::{
+        elements::{NativeElement, NativeElementType},
+        events::elements::Eventable,
+    };
+
+    fn key_press() -> UIEvent {
+        serde_json::from_str(r#"{"type": "onKeyPress", "key": "a"}"#).unwrap()
+    }
+
+    #[test]
+    fn listeners_can_register_listeners() {
+        let invoked = Rc::new(Cell::new(0));
+        let registered = Rc::new(RefCell::new(None));
+
+        let (inner_invoked, inner_registered) = (invoked.clone(), registered.clone());
+        let id = register_listener(Box::new(move |_| {
+            let invoked = inner_invoked.clone();
+            let id = register_listener(Box::new(move |_| invoked.set(invoked.get() + 1)));
+            inner_registered.replace(Some(id));
+        }));
+
+        invoke_listener(&id, &key_press());
+        let registered = registered.take().expect("registered by the listener");
+        invoke_listener(&registered, &key_press());
+
+        assert_eq!(invoked.get(), 1);
+        release_listeners(&[id, registered]);
+    }
+
+    #[test]
+    fn element_listeners_can_register_listeners() {
+        let mut element = NativeElement::new(NativeElementType::Pressable, None, None, None);
+        let registered = Rc::new(RefCell::new(vec![]));
+        let inner_registered = registered.clone();
+        element.add_event_listener(
+            "onKeyPress",
+            Box::new(move |_| {
+                let id = register_listener(Box::new(|_| {}));
+                inner_registered.borrow_mut().push(id);
+            }),
+        );
+
+        element.invoke_event("onKeyPress".to_string(), &key_press());
+        assert_eq!(registered.borrow().len(), 1);
+
+        element.remove_event_listener("onKeyPress");
+        release_listeners(&registered.take());
+    }
+
+    #[test]
+    fn stopped_events_do_not_reach_the_element() {
+        let mut element = NativeElement::new(NativeElementType::Pressable, None, None, None);
+        let invoked = Rc::new(Cell::new(false));
+        let inner_invoked = invoked.clone();
+        element.add_event_listener("onKeyPress", Box::new(move |_| inner_invoked.set(true)));
+
+        let event = key_press();
+        event.stop_propagation();
+        element.invoke_event("onKeyPress".to_string(), &event);
+
+        assert!(!invoked.get());
+        element.remove_event_listener("onKeyPress");
+    }
+
+    #[test]
+    fn entered_texts_disable_skipping_only_for_their_renderer() {
+        let typed = InstanceId {
+            component_id: Uuid::from_u128(1),
+            instance: 0,
+        };
+        let other = InstanceId {
+            component_id: Uuid::from_u128(2),
+            instance: 0,
+        };
+        let id = with_listener_owner(ListenerOwner::Instance(typed), || {
+            register_listener(Box::new(|_| {}))
+        });
+
+        ENTERED_TEXTS.with_borrow_mut(|texts| texts.insert(id.clone(), "Ada".to_string()));
+
+        assert!(has_entered_texts_for(typed));
+        assert!(!has_entered_texts_for(other));
+        clear_entered_texts();
+        release_listeners(&[id]);
+    }
 }
@@ -1,11 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{
-    ui::elements::{ExternalElement, NativeElement, UIElement, UIElementChildren},
-    utils::crypto::random_v4_uuid_str,
-};
+use crate::ui::elements::{ExternalElement, NativeElement, UIElement, UIElementChildren};
 
-use super::{bindings::REGISTERED_EVENTS, UIEvent};
+use super::{
+    bindings::{register_listener, registered_listeners, release_listeners},
+    UIEvent,
+};
 
 pub trait Eventable {
     fn add_event_listener(&mut self, name: &str, listener: Box<dyn FnMut(&UIEvent)>) -> String;
@@ -33,12 +33,7 @@ impl Eventable for NativeElement {
             None => return,
         };
 
-        REGISTERED_EVENTS.with(move |events| {
-            let mut borrowed = events.borrow_mut();
-            for id in ids {
-                borrowed.remove(id.as_str());
-            }
-        });
+        release_listeners(&ids.into_iter().collect::<Vec<_>>());
     }
     fn remove_event_listener_by_id(&mut self, name: &str, id: &str) {
         let id_removed = match &mut self.events {
@@ -54,7 +49,7 @@ impl Eventable for NativeElement {
             return;
         }
 
-        REGISTERED_EVENTS.with(move |events| events.borrow_mut().remove(id));
+        release_listeners(&[id.to_string()]);
     }
 
     fn invoke_event(&self, name: String, event: &UIEvent) {
@@ -68,21 +63,17 @@ impl Eventable for NativeElement {
             None => return,
         };
 
-        REGISTERED_EVENTS.with(|registered_events| {
-            let mut borrowed_registry = registered_events.borrow_mut();
-            for event_id in event_ids {
-                let listener = match borrowed_registry.get_mut(event_id) {
-                    Some(l) => l,
-                    None => continue,
-                };
-
-                listener(event);
-            }
-        });
+        // A stopped event does not reach the listeners of another element
+        if event.is_propagation_stopped() {
+            return;
+        }
+        for listener in registered_listeners(event_ids) {
+            (listener.borrow_mut())(event);
+        }
     }
 
     fn add_event_listener(&mut self, name: &str, listener: Box<dyn FnMut(&UIEvent)>) -> String {
-        let id = random_v4_uuid_str();
+        let id = register_listener(listener);
 
         let events = match &mut self.events {
             Some(e) => e,
@@ -93,8 +84,6 @@ impl Eventable for NativeElement {
 
         event_map.insert(id.clone());
 
-        REGISTERED_EVENTS.with(|events| events.borrow_mut().insert(id.clone(), listener));
-
         id
     }
     fn add_event_listener_self(&mut self, name: &str, listener: Box<dyn FnMut(&UIEvent)>) -> Self {
@@ -118,12 +107,7 @@ impl Eventable for ExternalElement {
             None => return,
         };
 
-        REGISTERED_EVENTS.with(move |events| {
-            let mut borrowed = events.borrow_mut();
-            for id in ids {
-                borrowed.remove(id.as_str());
-            }
-        });
+        release_listeners(&ids.into_iter().collect::<Vec<_>>());
     }
     fn remove_event_listener_by_id(&mut self, name: &str, id: &str) {
         let id_removed = match &mut self.events {
@@ -139,7 +123,7 @@ impl Eventable for ExternalElement {
             return;
         }
 
-        REGISTERED_EVENTS.with(move |events| events.borrow_mut().remove(id));
+        release_listeners(&[id.to_string()]);
     }
 
     fn invoke_event(&self, name: String, event: &UIEvent) {
@@ -153,21 +137,17 @@ impl Eventable for ExternalElement {
             None => return,
         };
 
-        REGISTERED_EVENTS.with(|registered_events| {
-            let mut borrowed_registry = registered_events.borrow_mut();
-            for event_id in event_ids {
-                let listener = match borrowed_registry.get_mut(event_id) {
-                    Some(l) => l,
-                    None => continue,
-                };
-
-                listener(event);
-            }
-        });
+        // A stopped event does not reach the listeners of another element
+        if event.is_propagation_stopped() {
+            return;
+        }
+        for listener in registered_listeners(event_ids) {
+            (listener.borrow_mut())(event);
+        }
     }
 
     fn add_event_listener(&mut self, name: &str, listener: Box<dyn FnMut(&UIEvent)>) -> String {
-        let id = random_v4_uuid_str();
+        let id = register_listener(listener);
 
         let events = match &mut self.events {
             Some(e) => e,
@@ -177,8 +157,6 @@ impl Eventable for ExternalElement {
         let event_map = events.entry(name.to_string()).or_insert_with(HashSet::new);
         event_map.insert(id.clone());
 
-        REGISTERED_EVENTS.with(|events| events.borrow_mut().insert(id.clone(), listener));
-
         id
     }
     fn add_event_listener_self(&mut self, name: &str, listener: Box<dyn FnMut(&UIEvent)>) -> Self {
@@ -1,18 +1,62 @@
-use std::collections::HashMap;
+use std::{cell::Cell, collections::HashMap, ops::Deref, rc::Rc};
 
-use native::{NativeLayoutEvent, NativeTouchEvent};
+use native::{
+    NativeEndReachedEvent, NativeFocusEvent, NativeImageErrorEvent, NativeImageLoadEvent,
+    NativeKeyPressEvent, NativeLayoutEvent, NativeScrollEvent, NativeTextEvent, NativeTouchEvent,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::logging;
+
 pub mod bindings;
 pub mod elements;
 pub mod native;
+mod propagation;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UIEvent {
     #[serde(flatten)]
     pub event_type: EventType,
+
+    #[serde(skip)]
+    control: Rc<EventControl>,
+}
+
+/// Propagation state shared by all listeners the event is dispatched to
+#[derive(Debug, Default)]
+struct EventControl {
+    propagation_stopped: Cell<bool>,
+    default_prevented: Cell<bool>,
+}
+
+impl UIEvent {
+    /// Stops the event from reaching the listeners of the other elements, once the listeners of the current element finish
+    pub fn stop_propagation(&self) {
+        self.control.propagation_stopped.set(true);
+    }
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.control.propagation_stopped.get()
+    }
+
+    /// Tells the host to skip its default behavior for the event, e.g. dismissing the keyboard on submit
+    pub fn prevent_default(&self) {
+        self.control.default_prevented.set(true);
+    }
+    pub fn is_default_prevented(&self) -> bool {
+        self.control.default_prevented.get()
+    }
+
+    /// Returns the text of the `on_change_text` and `on_submit_editing` events
+    pub fn text(&self) -> Option<&str> {
+        match &self.event_type {
+            EventType::OnChangeText(event) | EventType::OnSubmitEditing(event) => {
+                Some(event.text.as_str())
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,4 +75,139 @@ pub enum EventType {
     OnPress(NativeTouchEvent),
     OnPressIn(NativeTouchEvent),
     OnPressOut(NativeTouchEvent),
+
+    OnChangeText(NativeTextEvent),
+    OnSubmitEditing(NativeTextEvent),
+    OnFocus(NativeFocusEvent),
+    OnBlur(NativeFocusEvent),
+
+    OnKeyPress(NativeKeyPressEvent),
+
+    OnScroll(NativeScrollEvent),
+    OnScrollBeginDrag(NativeScrollEvent),
+    OnScrollEndDrag(NativeScrollEvent),
+    OnMomentumScrollEnd(NativeScrollEvent),
+    OnEndReached(NativeEndReachedEvent),
+
+    OnLoad(NativeImageLoadEvent),
+    OnError(NativeImageErrorEvent),
+}
+
+impl EventType {
+    /// Whether the plugin propagates the event to the parents of its element. Only press events do,
+    /// the host sends them to the pressed element alone. React Native bubbles the touch events itself
+    pub fn bubbles(&self) -> bool {
+        matches!(
+            self,
+            EventType::OnLongPress(_)
+                | EventType::OnPress(_)
+                | EventType::OnPressIn(_)
+                | EventType::OnPressOut(_)
+        )
+    }
+}
+
+/// Event with the payload of its kind, e.g. `on_scroll={|e: ScrollEvent| ..}`.
+/// The fields of the payload are accessible directly, e.g. `e.content_offset.y`
+#[derive(Debug, Clone)]
+pub struct Event<T> {
+    payload: T,
+    control: Rc<EventControl>,
+}
+impl<T> Event<T> {
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+
+    /// See [`UIEvent::stop_propagation`]
+    pub fn stop_propagation(&self) {
+        self.control.propagation_stopped.set(true);
+    }
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.control.propagation_stopped.get()
+    }
+
+    /// See [`UIEvent::prevent_default`]
+    pub fn prevent_default(&self) {
+        self.control.default_prevented.set(true);
+    }
+    pub fn is_default_prevented(&self) -> bool {
+        self.control.default_prevented.get()
+    }
+}
+impl<T> Deref for Event<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.payload
+    }
+}
+
+pub type LayoutEvent = Event<NativeLayoutEvent>;
+pub type TouchEvent = Event<NativeTouchEvent>;
+pub type PressEvent = Event<NativeTouchEvent>;
+pub type TextEvent = Event<NativeTextEvent>;
+pub type FocusEvent = Event<NativeFocusEvent>;
+pub type KeyPressEvent = Event<NativeKeyPressEvent>;
+pub type ScrollEvent = Event<NativeScrollEvent>;
+pub type EndReachedEvent = Event<NativeEndReachedEvent>;
+pub type ImageLoadEvent = Event<NativeImageLoadEvent>;
+pub type ImageErrorEvent = Event<NativeImageErrorEvent>;
+
+/// Argument of an event listener, created from the event the host sent
+pub trait EventPayload: Sized {
+    fn from_event(event: &UIEvent) -> Option<Self>;
+}
+impl EventPayload for UIEvent {
+    fn from_event(event: &UIEvent) -> Option<Self> {
+        Some(event.clone())
+    }
+}
+
+macro_rules! event_payload {
+    ($payload:ty => $($variant:ident),+) => {
+        impl EventPayload for Event<$payload> {
+            fn from_event(event: &UIEvent) -> Option<Self> {
+                match &event.event_type {
+                    $(EventType::$variant(payload))|+ => Some(Event {
+                        payload: payload.clone(),
+                        control: event.control.clone(),
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+event_payload!(NativeLayoutEvent => OnLayout);
+event_payload!(NativeTouchEvent => OnTouchStart, OnTouchEnd, OnTouchMove, OnTouchEndCapture, OnTouchCancel, OnLongPress, OnPress, OnPressIn, OnPressOut);
+event_payload!(NativeTextEvent => OnChangeText, OnSubmitEditing);
+event_payload!(NativeFocusEvent => OnFocus, OnBlur);
+event_payload!(NativeKeyPressEvent => OnKeyPress);
+event_payload!(NativeScrollEvent => OnScroll, OnScrollBeginDrag, OnScrollEndDrag, OnMomentumScrollEnd);
+event_payload!(NativeEndReachedEvent => OnEndReached);
+event_payload!(NativeImageLoadEvent => OnLoad);
+event_payload!(NativeImageErrorEvent => OnError);
+
+/// Wraps the typed listener of the `ui!` event attributes, which pass it the payload of their event kind.
+/// Events of another kind are logged and skipped
+pub fn listener<E, F>(mut listener: F) -> Box<dyn FnMut(&UIEvent)>
+where
+    E: EventPayload,
+    F: FnMut(E) + 'static,
+{
+    Box::new(move |event: &UIEvent| match E::from_event(event) {
+        Some(payload) => listener(payload),
+        None => logging::adv_error(
+            format!(
+                "Listener of {} received an event of another kind: {:?}",
+                std::any::type_name::<E>(),
+                event.event_type
+            )
+            .as_str(),
+            None,
+            false,
+        ),
+    })
 }
@@ -3,16 +3,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NativeLayoutEvent {
-    layout: LayoutRectangle,
+    pub layout: LayoutRectangle,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LayoutRectangle {
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,3 +37,87 @@ pub struct NativeTouchEvent {
     /// 3D Touch reported force (iOS only).
     pub force: Option<f64>,
 }
+
+/// Payload of the `on_change_text` and `on_submit_editing` events of a text input
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeTextEvent {
+    /// The current text of the input.
+    pub text: String,
+}
+
+/// Payload of the `on_focus` and `on_blur` events of a text input
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeFocusEvent {
+    /// The node ID of the element which gained or lost the focus.
+    pub target: Option<String>,
+}
+
+/// Payload of the `on_end_reached` event of a list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeEndReachedEvent {
+    /// Distance from the end of the content, in points.
+    pub distance_from_end: f64,
+}
+
+/// Payload of the scroll events of `ScrollView`, `FlatList` and `FlashList`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeScrollEvent {
+    /// The scrolled distance from the start of the content.
+    pub content_offset: Point,
+    /// The size of the whole content.
+    pub content_size: Size,
+    /// The size of the visible part of the content.
+    pub layout_measurement: Size,
+    /// The zoom of the content (iOS only).
+    pub zoom_scale: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Payload of the `on_key_press` event of a text input
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeKeyPressEvent {
+    /// The pressed key, e.g. `a`, `Enter` or `Backspace`.
+    pub key: String,
+}
+
+/// Payload of the `on_load` event of an image
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeImageLoadEvent {
+    /// The loaded image.
+    pub source: LoadedImageSource,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedImageSource {
+    pub uri: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Payload of the `on_error` event of an image
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeImageErrorEvent {
+    /// The reason why the image failed to load.
+    pub error: String,
+}
@@ -0,0 +1,69 @@
+use crate::ui::{bindings::with_rendered_instance, list, renderer::ElementListeners};
+
+use super::{
+    bindings::{invoke_listener, listener_owner, ListenerOwner},
+    UIEvent,
+};
+
+/// Dispatches the event the host sent to the listener through the plugin VDOM.
+///
+/// Bubbling events run the `*Capture` listeners from the root down to the element of the listener first,
+/// then the listener itself and the listeners of the same event on its parents up to the root.
+/// Events of the list items bubble through the list element to the root of its component
+pub(crate) fn dispatch_event(id: &str, event: &UIEvent) {
+    let path = if event.event_type.bubbles() {
+        find_listener_path(id)
+    } else {
+        None
+    };
+    let Some((name, path)) = path.and_then(|path| Some((event_name(&path, id)?, path))) else {
+        invoke_listener(id, event);
+        return;
+    };
+
+    // Capture listeners are invoked directly, without the capture phase of their own
+    if name.ends_with("Capture") {
+        invoke_listener(id, event);
+        return;
+    }
+
+    let capture = format!("{}Capture", name);
+    for listeners in &path {
+        invoke_listeners(listeners, &capture, event);
+        if event.is_propagation_stopped() {
+            return;
+        }
+    }
+
+    invoke_listener(id, event);
+    for listeners in path.iter().rev().skip(1) {
+        if event.is_propagation_stopped() {
+            return;
+        }
+        invoke_listeners(listeners, &name, event);
+    }
+}
+
+/// Returns the listeners of the elements on the path to the listener, searching only the tree owning it
+fn find_listener_path(id: &str) -> Option<Vec<ElementListeners>> {
+    match listener_owner(id)? {
+        ListenerOwner::Instance(instance) => {
+            with_rendered_instance(&instance, |renderer| renderer.listener_path(id))
+        }
+        ListenerOwner::List(list_id) => list::item_listener_path(&list_id, id),
+    }
+}
+
+/// Returns the event name the listener is registered for on its element, the last one of the path
+fn event_name(path: &[ElementListeners], id: &str) -> Option<String> {
+    path.last()?
+        .iter()
+        .find(|(_, ids)| ids.contains(id))
+        .map(|(name, _)| name.clone())
+}
+
+fn invoke_listeners(listeners: &ElementListeners, name: &str, event: &UIEvent) {
+    for id in listeners.get(name).into_iter().flatten() {
+        invoke_listener(id, event);
+    }
+}
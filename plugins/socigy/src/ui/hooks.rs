@@ -0,0 +1,364 @@
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use super::{
+    components::ChildInstance,
+    context::{context_version, providers_depth, truncate_providers},
+    events::TextEvent,
+    renderer::{mark_dirty, InstanceId},
+};
+use crate::warn;
+
+/// Identifies a nested component instance among the children of its parent
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ChildSlot {
+    type_id: TypeId,
+    key: ChildSlotKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ChildSlotKey {
+    Key(String),
+    /// Position among the unkeyed children of the same type
+    Index(usize),
+}
+
+/// Values of the hooks and the nested components of a single component instance
+#[derive(Default)]
+pub(crate) struct HookStore {
+    /// Hook values in the order the hooks are called
+    slots: Vec<Rc<dyn Any>>,
+    children: HashMap<ChildSlot, ChildInstance>,
+    /// Children rendered by the current render. The others are unmounted once it finishes
+    rendered_children: HashSet<ChildSlot>,
+    unkeyed_children: HashMap<TypeId, usize>,
+    /// Set when the state of the component or of any of its nested components changes
+    state_changed: Rc<Cell<bool>>,
+    /// Versions of the contexts read by the last render
+    consumed_contexts: HashMap<TypeId, u64>,
+}
+impl HookStore {
+    fn begin_render(&mut self) {
+        self.rendered_children.clear();
+        self.unkeyed_children.clear();
+        self.state_changed.set(false);
+        self.consumed_contexts.clear();
+    }
+
+    /// Returns whether the state or a context used by the last render changed since then.
+    /// Contexts are compared with the ones provided by the components which are rendering
+    pub(crate) fn needs_render(&self) -> bool {
+        self.state_changed.get()
+            || self
+                .consumed_contexts
+                .iter()
+                .any(|(type_id, version)| context_version(*type_id) != *version)
+    }
+
+    pub(crate) fn children_mut(&mut self) -> impl Iterator<Item = &mut ChildInstance> {
+        self.children.values_mut()
+    }
+
+    fn finish_render(&mut self) -> Vec<ChildInstance> {
+        let rendered = std::mem::take(&mut self.rendered_children);
+        let removed = self
+            .children
+            .keys()
+            .filter(|slot| !rendered.contains(*slot))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        removed
+            .iter()
+            .filter_map(|slot| self.children.remove(slot))
+            .collect()
+    }
+}
+
+/// The component instance owning a hook
+#[derive(Clone)]
+pub(crate) struct HookOwner {
+    renderer_id: InstanceId,
+    /// State change flags of the component and all of its ancestors, which must render again to render it
+    changed_flags: Rc<Vec<Rc<Cell<bool>>>>,
+}
+impl HookOwner {
+    pub(crate) fn renderer_id(&self) -> InstanceId {
+        self.renderer_id
+    }
+
+    /// Marks the component and its ancestors as changed and schedules a render of their renderer
+    fn mark_changed(&self) {
+        for flag in self.changed_flags.iter() {
+            flag.set(true);
+        }
+        mark_dirty(self.renderer_id);
+    }
+
+    /// Returns whether both owners are the same component instance
+    fn is_same(&self, other: &HookOwner) -> bool {
+        match (self.changed_flags.last(), other.changed_flags.last()) {
+            (Some(flag), Some(other)) => Rc::ptr_eq(flag, other),
+            _ => false,
+        }
+    }
+}
+
+struct HookContext {
+    owner: HookOwner,
+    store: Rc<RefCell<HookStore>>,
+    index: usize,
+    /// Number of contexts provided by the ancestors, the contexts above are provided by the rendering component
+    providers_depth: usize,
+}
+
+thread_local! {
+    static HOOK_CONTEXT: RefCell<Option<HookContext>> = const { RefCell::new(None) };
+    /// Components whose render read the cached state of the permission
+    static PERMISSION_READERS: RefCell<HashMap<String, Vec<HookOwner>>> = RefCell::new(HashMap::new());
+}
+
+/// Makes the hook store available to the hooks called inside of the function, e.g. while the component renders
+pub(crate) fn with_hooks<T>(
+    renderer_id: InstanceId,
+    store: Rc<RefCell<HookStore>>,
+    function: impl FnOnce() -> T,
+) -> T {
+    /// Restores the outer context and removes the provided contexts even if the render panics
+    struct ContextGuard(Option<HookContext>, usize);
+    impl Drop for ContextGuard {
+        fn drop(&mut self) {
+            HOOK_CONTEXT.set(self.0.take());
+            truncate_providers(self.1);
+        }
+    }
+
+    store.borrow_mut().begin_render();
+
+    let mut changed_flags = HOOK_CONTEXT.with_borrow(|context| {
+        context
+            .as_ref()
+            .map(|context| context.owner.changed_flags.as_ref().clone())
+            .unwrap_or_default()
+    });
+    changed_flags.push(store.borrow().state_changed.clone());
+
+    let result = {
+        let depth = providers_depth();
+        let _guard = ContextGuard(
+            HOOK_CONTEXT.replace(Some(HookContext {
+                owner: HookOwner {
+                    renderer_id,
+                    changed_flags: Rc::new(changed_flags),
+                },
+                store: store.clone(),
+                index: 0,
+                providers_depth: depth,
+            })),
+            depth,
+        );
+        function()
+    };
+
+    // The borrow must be released before the removed children are dropped, as they run their unmount
+    let removed = store.borrow_mut().finish_render();
+    drop(removed);
+
+    result
+}
+
+/// Returns the owner of the rendering component and the value of the next hook, creating it on the first render
+pub(crate) fn next_hook<T: 'static>(name: &str, init: impl FnOnce() -> T) -> (HookOwner, Rc<T>) {
+    let (owner, store, index) = HOOK_CONTEXT.with_borrow_mut(|context| {
+        let context = context.as_mut().unwrap_or_else(|| {
+            panic!("{} can be called only while a component renders", name)
+        });
+        context.index += 1;
+
+        (context.owner.clone(), context.store.clone(), context.index - 1)
+    });
+
+    // Computed before the store is borrowed, as `init` may read a context, which records it in the store
+    if index == store.borrow().slots.len() {
+        let value = Rc::new(init());
+        store.borrow_mut().slots.push(value);
+    }
+
+    let slot = store.borrow().slots[index].clone();
+    match slot.downcast::<T>() {
+        Ok(value) => (owner, value),
+        Err(_) => panic!(
+            "{} was called in a different order than in the previous render. Hooks must not be called conditionally",
+            name
+        ),
+    }
+}
+
+/// Returns the number of contexts provided by the ancestors of the rendering component, if any component renders
+pub(crate) fn ancestor_providers_depth() -> Option<usize> {
+    HOOK_CONTEXT.with_borrow(|context| context.as_ref().map(|context| context.providers_depth))
+}
+
+/// Remembers the version of the context read by the rendering component
+pub(crate) fn record_consumed_context(type_id: TypeId, version: u64) {
+    HOOK_CONTEXT.with_borrow(|context| {
+        if let Some(context) = context.as_ref() {
+            context
+                .store
+                .borrow_mut()
+                .consumed_contexts
+                .insert(type_id, version);
+        }
+    });
+}
+
+/// Remembers that the rendering component read the cached state of the permission
+pub(crate) fn record_consumed_permission(name: &str) {
+    let Some(owner) =
+        HOOK_CONTEXT.with_borrow(|context| context.as_ref().map(|context| context.owner.clone()))
+    else {
+        return;
+    };
+
+    PERMISSION_READERS.with_borrow_mut(|readers| {
+        let readers = readers.entry(name.to_string()).or_default();
+        if !readers.iter().any(|reader| reader.is_same(&owner)) {
+            readers.push(owner);
+        }
+    });
+}
+
+/// Schedules a render of the components which read the permission. They are recorded again by the render
+pub(crate) fn permission_changed(name: &str) {
+    let readers = PERMISSION_READERS.with_borrow_mut(|readers| readers.remove(name));
+    for reader in readers.into_iter().flatten() {
+        reader.mark_changed();
+    }
+}
+
+/// Reserves the slot of a nested component of the type. Returns `None` if no component is rendering
+pub(crate) fn child_slot<T: 'static>(key: Option<String>) -> Option<(InstanceId, ChildSlot)> {
+    HOOK_CONTEXT.with_borrow(|context| {
+        let context = context.as_ref()?;
+        let mut store = context.store.borrow_mut();
+        let type_id = TypeId::of::<T>();
+
+        let keyed_slot = key.map(|key| ChildSlot {
+            type_id,
+            key: ChildSlotKey::Key(key),
+        });
+        let slot = match keyed_slot {
+            Some(slot) if !store.rendered_children.contains(&slot) => slot,
+            slot => {
+                if let Some(ChildSlot {
+                    key: ChildSlotKey::Key(key),
+                    ..
+                }) = slot
+                {
+                    warn!(
+                        "Component {} with key '{}' is rendered more than once. Please assign unique keys",
+                        std::any::type_name::<T>(),
+                        key
+                    );
+                }
+
+                let index = store.unkeyed_children.entry(type_id).or_default();
+                *index += 1;
+                ChildSlot {
+                    type_id,
+                    key: ChildSlotKey::Index(*index - 1),
+                }
+            }
+        };
+
+        store.rendered_children.insert(slot.clone());
+        Some((context.owner.renderer_id, slot))
+    })
+}
+
+/// Takes the nested component instance out of the store of the rendering component, so it can render
+pub(crate) fn take_child(slot: &ChildSlot) -> Option<ChildInstance> {
+    HOOK_CONTEXT.with_borrow(|context| {
+        context
+            .as_ref()?
+            .store
+            .borrow_mut()
+            .children
+            .remove(slot)
+    })
+}
+
+/// Returns the rendered nested component instance to the store of the rendering component
+pub(crate) fn return_child(slot: ChildSlot, instance: ChildInstance) {
+    HOOK_CONTEXT.with_borrow(|context| {
+        if let Some(context) = context.as_ref() {
+            context.store.borrow_mut().children.insert(slot, instance);
+        }
+    });
+}
+
+/// Component state kept across renders. Changing it schedules a render of the component
+pub struct State<T> {
+    value: Rc<RefCell<T>>,
+    /// Rendered again when the state changes. Nested components are rendered by the renderer of their root
+    owner: HookOwner,
+}
+impl<T> Clone for State<T> {
+    fn clone(&self) -> Self {
+        State {
+            value: self.value.clone(),
+            owner: self.owner.clone(),
+        }
+    }
+}
+impl<T: 'static> State<T> {
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.borrow().clone()
+    }
+
+    pub fn with<R>(&self, function: impl FnOnce(&T) -> R) -> R {
+        function(&self.value.borrow())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+        self.owner.mark_changed();
+    }
+
+    pub fn update(&self, function: impl FnOnce(&mut T)) {
+        function(&mut self.value.borrow_mut());
+        self.owner.mark_changed();
+    }
+}
+
+impl State<String> {
+    /// Returns an `on_change_text` listener storing the entered text, which binds a controlled input to the state:
+    /// `<TextInput value={name.get()} on_change_text={name.bind_text()} />`
+    pub fn bind_text(&self) -> impl FnMut(TextEvent) + 'static {
+        let state = self.clone();
+        move |event: TextEvent| {
+            let text = event.text.as_str();
+
+            // Typing the text the state already holds, e.g. the value set by the last render, does not render again
+            if state.with(|value| value != text) {
+                state.set(text.to_string());
+            }
+        }
+    }
+}
+
+/// Returns the state of the rendering component. The initial value is created only on the first render.
+///
+/// Hooks must be called in the same order on every render, so they can not be called conditionally
+pub fn use_state<T: 'static>(initial: impl FnOnce() -> T) -> State<T> {
+    let (owner, value) = next_hook("use_state", || RefCell::new(initial()));
+
+    State { value, owner }
+}
@@ -0,0 +1,257 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{
+    bindings::with_rendered_instance,
+    elements::UIElement,
+    events::bindings::{with_listener_owner, ListenerOwner},
+    hooks::next_hook,
+    renderer::{
+        diff_elements, listener_path, release_element_listeners, ElementListeners, InstanceId,
+    },
+};
+use crate::{logging, utils::crypto::random_v4_uuid_str};
+
+#[wasm_bindgen(js_namespace = ["socigy", "ui", "render"])]
+extern "C" {
+    #[wasm_bindgen(js_name = "processListItemChanges")]
+    fn process_list_item_changes(list_id: String, key: String, changes: String);
+}
+
+/// Items of a virtualized list, passed to the `data` prop of `FlatList` and `FlashList`.
+/// The host renders only the visible items, which it requests by their index
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListData {
+    pub id: String,
+    pub keys: Vec<String>,
+}
+
+type ItemRenderer = Rc<dyn Fn(usize) -> Option<UIElement>>;
+
+struct ListSource {
+    renderer_id: InstanceId,
+    keys: Vec<String>,
+    render_item: ItemRenderer,
+    /// Items shown by the host, with the listener ids known to it
+    rendered: HashMap<String, Option<UIElement>>,
+}
+
+thread_local! {
+    static LISTS: RefCell<HashMap<String, ListSource>> = RefCell::new(HashMap::new());
+    /// Lists whose component rendered again, so their shown items have to be rendered again too
+    static STALE_LISTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Hook value owning the list. The list is removed once its component is dropped
+struct ListSlot {
+    id: String,
+}
+impl Drop for ListSlot {
+    fn drop(&mut self) {
+        let removed = LISTS.with_borrow_mut(|lists| lists.remove(&self.id));
+        if let Some(list) = removed {
+            list.rendered
+                .values()
+                .flatten()
+                .for_each(release_element_listeners);
+        }
+        STALE_LISTS.with_borrow_mut(|stale| stale.remove(&self.id));
+    }
+}
+
+/// Returns a virtualized list of `count` items for the `data` prop of `FlatList` or `FlashList`.
+/// Items are rendered lazily once the host shows them, and the shown items are rendered again with every render of the component.
+///
+/// The item renderer runs outside of the component render, so it can not call hooks
+pub fn use_list(
+    count: usize,
+    key_extractor: impl Fn(usize) -> String,
+    render_item: impl Fn(usize) -> Option<UIElement> + 'static,
+) -> ListData {
+    let (owner, slot) = next_hook("use_list", || ListSlot {
+        id: random_v4_uuid_str(),
+    });
+    let keys = (0..count).map(key_extractor).collect::<Vec<_>>();
+
+    LISTS.with_borrow_mut(|lists| {
+        let list = lists.entry(slot.id.clone()).or_insert_with(|| ListSource {
+            renderer_id: owner.renderer_id(),
+            keys: vec![],
+            render_item: Rc::new(|_| None),
+            rendered: HashMap::new(),
+        });
+        list.keys = keys.clone();
+        list.render_item = Rc::new(render_item);
+
+        if !list.rendered.is_empty() {
+            STALE_LISTS.with_borrow_mut(|stale| stale.insert(slot.id.clone()));
+        }
+    });
+
+    ListData {
+        id: slot.id.clone(),
+        keys,
+    }
+}
+
+/// Renders the item of the list, using its key as the key of the element
+fn render_item(list_id: &str, index: usize) -> Option<(String, Option<UIElement>)> {
+    let (renderer_id, key, render_item) = LISTS.with_borrow(|lists| {
+        let list = lists.get(list_id)?;
+        Some((
+            list.renderer_id,
+            list.keys.get(index)?.clone(),
+            list.render_item.clone(),
+        ))
+    })?;
+
+    let mut element = with_listener_owner(ListenerOwner::List(list_id.to_string()), || {
+        logging::with_component_scope(renderer_id.to_string(), || render_item(index))
+    });
+    if let Some(element) = element.as_mut() {
+        let element_key = match element {
+            UIElement::Native(native) => &mut native.key,
+            UIElement::External(external) => &mut external.key,
+        };
+        if element_key.is_none() {
+            *element_key = Some(key.clone());
+        }
+    }
+
+    Some((key, element))
+}
+
+/// Stores the rendered item, releasing the listeners of its previous render
+fn store_item(list_id: &str, key: String, element: Option<UIElement>) {
+    let previous = LISTS.with_borrow_mut(|lists| {
+        lists
+            .get_mut(list_id)
+            .and_then(|list| list.rendered.insert(key, element))
+    });
+
+    if let Some(Some(previous)) = previous {
+        release_element_listeners(&previous);
+    }
+}
+
+/// Renders the shown items of the lists whose component rendered and sends their changes to the host
+pub(crate) fn render_stale_lists() {
+    for list_id in STALE_LISTS.take() {
+        let shown = LISTS.with_borrow(|lists| {
+            lists.get(&list_id).map(|list| {
+                list.rendered
+                    .keys()
+                    .map(|key| (key.clone(), list.keys.iter().position(|k| k == key)))
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        for (key, index) in shown.into_iter().flatten() {
+            let Some(index) = index else {
+                // The item was removed from the list, the host removes it with the next render of the list
+                release_list_item(list_id.clone(), key);
+                continue;
+            };
+            let Some((key, mut element)) = render_item(&list_id, index) else {
+                continue;
+            };
+
+            let previous = LISTS.with_borrow_mut(|lists| {
+                lists
+                    .get_mut(&list_id)
+                    .and_then(|list| list.rendered.remove(&key))
+                    .flatten()
+            });
+            let changes = diff_elements(previous.as_ref(), element.as_mut());
+            store_item(&list_id, key.clone(), element);
+
+            if !changes.is_empty() {
+                process_list_item_changes(
+                    list_id.clone(),
+                    key,
+                    serde_json::json!(changes).to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the listeners of the elements from the root of the list component to the element holding the listener id.
+/// The path continues from the list element through the shown item holding the listener
+pub(crate) fn item_listener_path(list_id: &str, id: &str) -> Option<Vec<ElementListeners>> {
+    let (renderer_id, item_path) = LISTS.with_borrow(|lists| {
+        let list = lists.get(list_id)?;
+        let item_path = list
+            .rendered
+            .values()
+            .flatten()
+            .find_map(|item| listener_path(item, id))?;
+        Some((list.renderer_id, item_path))
+    })?;
+
+    let mut path = with_rendered_instance(&renderer_id, |renderer| renderer.list_path(list_id))
+        .unwrap_or_default();
+    path.extend(item_path);
+    Some(path)
+}
+
+/// Returns the id of the list whose shown items hold the listener id, searching all of them
+pub(crate) fn find_listener_list(id: &str) -> Option<String> {
+    LISTS.with_borrow(|lists| {
+        lists
+            .iter()
+            .find(|(_, list)| {
+                list.rendered
+                    .values()
+                    .flatten()
+                    .any(|item| listener_path(item, id).is_some())
+            })
+            .map(|(list_id, _)| list_id.clone())
+    })
+}
+
+/// Returns the instance rendering the list
+pub(crate) fn list_renderer_id(list_id: &str) -> Option<InstanceId> {
+    LISTS.with_borrow(|lists| lists.get(list_id).map(|list| list.renderer_id))
+}
+
+/// Renders the item of the list once the host shows it. Returns `None` if the list or the item do not exist
+#[wasm_bindgen]
+pub fn render_list_item(list_id: String, index: u32) -> Option<String> {
+    let (key, element) = render_item(&list_id, index as usize)?;
+
+    let result = match serde_json::to_string(&element) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            logging::adv_error(
+                format!("Failed to serialize list item: {}", e).as_str(),
+                None,
+                false,
+            );
+            None
+        }
+    };
+
+    store_item(&list_id, key, element);
+    result
+}
+
+/// Frees the listeners of the item once the host stops showing it
+#[wasm_bindgen]
+pub fn release_list_item(list_id: String, key: String) {
+    let released = LISTS.with_borrow_mut(|lists| {
+        lists
+            .get_mut(&list_id)
+            .and_then(|list| list.rendered.remove(&key))
+    });
+
+    if let Some(Some(element)) = released {
+        release_element_listeners(&element);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod bindings;
 pub mod components;
+pub mod context;
 pub mod elements;
 pub mod events;
+pub mod hooks;
+pub mod list;
 pub mod renderer;
+pub mod style;
 
 #[cfg(feature = "ui-macros")]
 pub use socigy_macros::ui;
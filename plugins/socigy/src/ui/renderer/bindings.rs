@@ -3,5 +3,12 @@ use wasm_bindgen::prelude::wasm_bindgen;
 #[wasm_bindgen(js_namespace = ["socigy", "ui", "render"])]
 extern "C" {
     #[wasm_bindgen(js_name = "processComponentRenderChanges")]
-    pub fn process_component_render_changes(id: String, changes: String);
+    pub fn process_component_render_changes(id: String, changes: String, instance: u32);
+
+    /// Asks the host to invoke [`super::scheduler::on_frame`] before the next frame is drawn
+    #[wasm_bindgen(js_name = "requestFrame")]
+    pub fn request_frame();
+
+    #[wasm_bindgen(js_name = "onComponentError")]
+    pub fn on_component_error(id: String, error: String, instance: u32);
 }
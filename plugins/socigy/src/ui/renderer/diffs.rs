@@ -1,12 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
     error,
-    ui::elements::{
-        NativeElement, NativeElementProps, NativeElementType, UIElement, UIElementChildren,
+    ui::{
+        elements::{
+            flatten_children, FlatChild, NativeElement, NativeElementProps, NativeElementType,
+            UIElement, UIElementChildren,
+        },
+        events::bindings::{
+            alias_listener, has_entered_texts, release_listeners, take_entered_text,
+            transfer_listeners,
+        },
     },
 };
 
@@ -20,6 +27,12 @@ pub enum VDOMChange {
     RemoveElement {
         path: Vec<i32>,
     },
+    /// Moves the element at the path to the `to` index of the same parent.
+    /// The index is applied after the element is taken out of the children
+    MoveElement {
+        path: Vec<i32>,
+        to: i32,
+    },
     ReplaceElement {
         path: Vec<i32>,
         element: UIElement,
@@ -55,20 +68,250 @@ pub enum VDOMChange {
     RemoveChildren {
         path: Vec<i32>,
     },
+    /// Changed properties of the `style` prop. Removed properties are `null`
     UpdateStyle {
         path: Vec<i32>,
         styles: HashMap<String, Value>,
     },
+    AddEventListener {
+        path: Vec<i32>,
+        name: String,
+        id: String,
+    },
+    RemoveEventListener {
+        path: Vec<i32>,
+        name: String,
+        id: String,
+    },
     RemoveAll,
 }
 
+/// Name of the serialized `style` prop of the native elements
+const STYLE_PROP: &str = "style";
+/// Name of the serialized `value` prop of the text inputs
+const VALUE_PROP: &str = "value";
+const CHANGE_TEXT_EVENT: &str = "onChangeText";
+
+type ElementEvents = Option<HashMap<String, HashSet<String>>>;
+/// Event name and listener id
+type Listener = (String, String);
+
+fn element_events(element: &UIElement) -> &ElementEvents {
+    match element {
+        UIElement::Native(native) => &native.events,
+        UIElement::External(external) => &external.events,
+    }
+}
+
+fn element_children(element: &UIElement) -> &Option<Vec<UIElementChildren>> {
+    match element {
+        UIElement::Native(native) => &native.children,
+        UIElement::External(external) => &external.children,
+    }
+}
+
+fn child_elements(children: &Option<Vec<UIElementChildren>>) -> Vec<&UIElement> {
+    let mut elements = vec![];
+    for child in children.iter().flatten() {
+        match child {
+            UIElementChildren::Element(element) => elements.push(element),
+            UIElementChildren::OptionalElements(nested) => elements.extend(nested.iter().flatten()),
+            UIElementChildren::Elements(nested) => elements.extend(nested.iter()),
+            _ => {}
+        }
+    }
+
+    elements
+}
+
+/// Listeners matched or freed by the diff. Closures of the new render take over the ids already known to the host,
+/// so only listeners that were really added or removed are sent as changes
+#[derive(Default)]
+pub struct ListenerChanges {
+    /// New listener id -> old listener id
+    renamed: HashMap<String, String>,
+    released: Vec<String>,
+}
+impl ListenerChanges {
+    /// Frees every listener of the element and its descendants
+    pub fn release_element(&mut self, element: &UIElement) {
+        for ids in element_events(element).iter().flat_map(|events| events.values()) {
+            self.released.extend(ids.iter().cloned());
+        }
+
+        for child in child_elements(element_children(element)) {
+            self.release_element(child);
+        }
+    }
+
+    /// Pairs listeners with the same event name and returns the unpaired new and old listeners
+    fn pair(
+        &mut self,
+        old_events: &ElementEvents,
+        new_events: &ElementEvents,
+    ) -> (Vec<Listener>, Vec<Listener>) {
+        let empty = HashSet::new();
+        let mut names: Vec<&String> = old_events
+            .iter()
+            .chain(new_events.iter())
+            .flat_map(|events| events.keys())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut added = vec![];
+        let mut removed = vec![];
+        for name in names {
+            let old_ids = old_events.as_ref().and_then(|e| e.get(name)).unwrap_or(&empty);
+            let new_ids = new_events.as_ref().and_then(|e| e.get(name)).unwrap_or(&empty);
+
+            let mut old_ids = old_ids.iter();
+            let mut new_ids = new_ids.iter();
+            loop {
+                match (old_ids.next(), new_ids.next()) {
+                    (Some(old_id), Some(new_id)) => {
+                        self.renamed.insert(new_id.clone(), old_id.clone());
+                    }
+                    (None, Some(new_id)) => added.push((name.clone(), new_id.clone())),
+                    (Some(old_id), None) => {
+                        self.released.push(old_id.clone());
+                        removed.push((name.clone(), old_id.clone()));
+                    }
+                    (None, None) => break,
+                }
+            }
+        }
+
+        (added, removed)
+    }
+
+    /// Pairs listeners of two subtrees with the same structure, e.g. when the diff skipped them by their hash
+    fn transfer_subtree(&mut self, old: &UIElement, new: &UIElement) {
+        self.pair(element_events(old), element_events(new));
+
+        let old_children = child_elements(element_children(old));
+        let new_children = child_elements(element_children(new));
+        for (old_child, new_child) in old_children.into_iter().zip(new_children) {
+            self.transfer_subtree(old_child, new_child);
+        }
+    }
+
+    /// Moves the new closures to the old ids, frees the released closures and renames the ids in the new tree
+    pub fn apply(self, new: &mut UIElement) {
+        transfer_listeners(&self.renamed);
+        release_listeners(&self.released);
+
+        if !self.renamed.is_empty() {
+            self.rename_ids(new);
+        }
+    }
+
+    /// Frees the released closures, used when there is no new tree
+    pub fn apply_releases(self) {
+        release_listeners(&self.released);
+    }
+
+    fn rename_ids(&self, element: &mut UIElement) {
+        map_listener_ids(element, &mut |id| self.renamed.get(&id).cloned().unwrap_or(id));
+    }
+}
+
+/// Replaces every listener id of the element and its descendants
+fn map_listener_ids(element: &mut UIElement, map: &mut impl FnMut(String) -> String) {
+    let (events, children) = match element {
+        UIElement::Native(native) => (&mut native.events, &mut native.children),
+        UIElement::External(external) => (&mut external.events, &mut external.children),
+    };
+
+    for ids in events.iter_mut().flat_map(|events| events.values_mut()) {
+        *ids = ids.drain().map(&mut *map).collect();
+    }
+
+    for child in children.iter_mut().flatten() {
+        match child {
+            UIElementChildren::Element(element) => map_listener_ids(element, map),
+            UIElementChildren::OptionalElements(nested) => nested
+                .iter_mut()
+                .flatten()
+                .for_each(|e| map_listener_ids(e, map)),
+            UIElementChildren::Elements(nested) => {
+                nested.iter_mut().for_each(|e| map_listener_ids(e, map))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Copies the element with its listeners registered under new ids, so the copy can be rendered
+/// while the original ids stay owned by the original element
+pub(crate) fn share_listeners(element: &UIElement) -> UIElement {
+    let mut shared = element.clone();
+    map_listener_ids(&mut shared, &mut |id| alias_listener(&id).unwrap_or(id));
+
+    shared
+}
+
+/// Listeners of one element by their event name
+pub(crate) type ElementListeners = HashMap<String, HashSet<String>>;
+
+/// Returns the listeners of the elements from the root to the element holding the listener id
+pub(crate) fn listener_path(element: &UIElement, id: &str) -> Option<Vec<ElementListeners>> {
+    element_path(element, &|element| {
+        element_events(element)
+            .iter()
+            .flat_map(|events| events.values())
+            .any(|ids| ids.contains(id))
+    })
+}
+
+/// Returns the listeners of the elements from the root to the list element showing the items of the list
+pub(crate) fn list_path(element: &UIElement, list_id: &str) -> Option<Vec<ElementListeners>> {
+    element_path(element, &|element| {
+        let data = match element {
+            UIElement::Native(native) => match native.props.as_deref() {
+                Some(NativeElementProps::FlatList(list)) => list.data.as_ref(),
+                Some(NativeElementProps::FlashList(list)) => list.data.as_ref(),
+                _ => None,
+            },
+            UIElement::External(_) => None,
+        };
+        data.is_some_and(|data| data.id == list_id)
+    })
+}
+
+/// Returns the listeners of the elements from the root to the first element matching the predicate
+fn element_path(
+    element: &UIElement,
+    matches: &impl Fn(&UIElement) -> bool,
+) -> Option<Vec<ElementListeners>> {
+    let events = element_events(element);
+    if matches(element) {
+        return Some(vec![events.clone().unwrap_or_default()]);
+    }
+
+    child_elements(element_children(element))
+        .into_iter()
+        .find_map(|child| {
+            let mut path = element_path(child, matches)?;
+            path.insert(0, events.clone().unwrap_or_default());
+            Some(path)
+        })
+}
+
+/// Frees the listeners of an element which is not part of any rendered tree, e.g. a cached render
+pub(crate) fn release_element_listeners(element: &UIElement) {
+    let mut listeners = ListenerChanges::default();
+    listeners.release_element(element);
+    listeners.apply_releases();
+}
+
 fn external_props_as_object(external_props: Option<HashMap<String, Value>>) -> Option<Value> {
     match external_props {
         Some(val) => Some(serde_json::value::Value::Object(val.into_iter().collect())),
         None => None,
     }
 }
-fn native_props_as_object(native_props: Option<NativeElementProps>) -> Option<Value> {
+fn native_props_as_object(native_props: Option<&NativeElementProps>) -> Option<Value> {
     match native_props {
         Some(val) => match serde_json::to_value(val) {
             Ok(value) => Some(value),
@@ -86,10 +329,22 @@ pub fn compare_element(
     old: &UIElement,
     new: &UIElement,
     changes: &mut Vec<VDOMChange>,
+    listeners: &mut ListenerChanges,
 ) {
+    // Unchanged subtrees do not have to be walked, only their listeners have to be taken over
+    if old.content_hash() == new.content_hash() {
+        listeners.transfer_subtree(old, new);
+        // Texts entered into the inputs are not part of the rendered UI, so they are checked separately
+        if has_entered_texts() {
+            correct_entered_texts(index, old, new, changes);
+        }
+        return;
+    }
+
     match (old, new) {
         (UIElement::Native(old_native), UIElement::Native(new_native)) => {
             if old_native.native_type != new_native.native_type {
+                listeners.release_element(old);
                 changes.push(VDOMChange::ReplaceElement {
                     path: index.clone(),
                     element: new.clone(),
@@ -99,14 +354,23 @@ pub fn compare_element(
 
             compare_props(
                 index,
-                &native_props_as_object(old_native.props.clone()),
-                &native_props_as_object(new_native.props.clone()),
+                &native_props_as_object(old_native.props.as_deref()),
+                &native_props_as_object(new_native.props.as_deref()),
                 changes,
             );
-            compare_children(index, &old_native.children, &new_native.children, changes);
+            correct_entered_text(index, old_native, new_native, changes);
+            compare_events(index, &old_native.events, &new_native.events, changes, listeners);
+            compare_children(
+                index,
+                &old_native.children,
+                &new_native.children,
+                changes,
+                listeners,
+            );
         }
         (UIElement::External(old_ext), UIElement::External(new_ext)) => {
             if old_ext.id != new_ext.id {
+                listeners.release_element(old);
                 changes.push(VDOMChange::ReplaceElement {
                     path: index.clone(),
                     element: new.clone(),
@@ -120,9 +384,11 @@ pub fn compare_element(
                 &external_props_as_object(new_ext.props.clone()),
                 changes,
             );
-            compare_children(index, &old_ext.children, &new_ext.children, changes);
+            compare_events(index, &old_ext.events, &new_ext.events, changes, listeners);
+            compare_children(index, &old_ext.children, &new_ext.children, changes, listeners);
         }
         _ => {
+            listeners.release_element(old);
             changes.push(VDOMChange::ReplaceElement {
                 path: index.clone(),
                 element: new.clone(),
@@ -131,6 +397,98 @@ pub fn compare_element(
     }
 }
 
+/// Sends the unchanged value of a controlled text input again if the user entered a different text, e.g. because
+/// the component rejected it. The host keeps showing the entered text otherwise
+fn correct_entered_text(
+    index: &[i32],
+    old: &NativeElement,
+    new: &NativeElement,
+    changes: &mut Vec<VDOMChange>,
+) {
+    if old.native_type != NativeElementType::TextInput {
+        return;
+    }
+
+    // The old element holds the listener ids known to the host
+    let Some(entered) = old
+        .events
+        .iter()
+        .flat_map(|events| events.get(CHANGE_TEXT_EVENT))
+        .flatten()
+        .find_map(|id| take_entered_text(id))
+    else {
+        return;
+    };
+
+    let value = |native: &NativeElement| {
+        native_props_as_object(native.props.as_deref())?
+            .get(VALUE_PROP)
+            .cloned()
+    };
+    // A changed value is already sent by the props diff
+    let Some(value) = value(new).filter(|new_value| Some(new_value) == value(old).as_ref()) else {
+        return;
+    };
+    if value.as_str() != Some(entered.as_str()) {
+        changes.push(VDOMChange::UpdateProp {
+            path: index.to_vec(),
+            key: VALUE_PROP.to_string(),
+            value,
+        });
+    }
+}
+
+/// Checks the entered texts of the inputs in two subtrees with the same content, see [`correct_entered_text`]
+fn correct_entered_texts(
+    index: &mut Vec<i32>,
+    old: &UIElement,
+    new: &UIElement,
+    changes: &mut Vec<VDOMChange>,
+) {
+    if let (UIElement::Native(old_native), UIElement::Native(new_native)) = (old, new) {
+        correct_entered_text(index, old_native, new_native, changes);
+    }
+
+    let old_children = flatten_children(element_children(old).as_deref().unwrap_or_default());
+    let new_children = flatten_children(element_children(new).as_deref().unwrap_or_default());
+    for (position, children) in old_children.iter().zip(&new_children).enumerate() {
+        if let (FlatChild::Element(old_child), FlatChild::Element(new_child)) = children {
+            index.push(position as i32);
+            correct_entered_texts(index, old_child, new_child, changes);
+            index.pop();
+        }
+    }
+}
+
+fn compare_events(
+    index: &[i32],
+    old_events: &ElementEvents,
+    new_events: &ElementEvents,
+    changes: &mut Vec<VDOMChange>,
+    listeners: &mut ListenerChanges,
+) {
+    let (added, removed) = listeners.pair(old_events, new_events);
+
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|(name, id)| VDOMChange::RemoveEventListener {
+                path: index.to_vec(),
+                name,
+                id,
+            }),
+    );
+    changes.extend(
+        added
+            .into_iter()
+            .map(|(name, id)| VDOMChange::AddEventListener {
+                path: index.to_vec(),
+                name,
+                id,
+            }),
+    );
+}
+
 fn compare_props(
     index: &Vec<i32>,
     old_props: &Option<serde_json::Value>,
@@ -149,6 +507,13 @@ fn compare_props(
                 .unwrap_or_else(serde_json::Map::new);
 
             for (key, new_value) in &new_map {
+                if let (Some(Value::Object(old_style)), Value::Object(new_style)) =
+                    (old_map.get(key).filter(|_| key == STYLE_PROP), new_value)
+                {
+                    compare_style(index, old_style, new_style, changes);
+                    continue;
+                }
+
                 if old_map.get(key) != Some(new_value) {
                     changes.push(VDOMChange::UpdateProp {
                         path: index.clone(),
@@ -182,63 +547,799 @@ fn compare_props(
     }
 }
 
+/// Compares two renders of a tree which is not part of a component VDOM, e.g. an item of a virtualized list.
+/// The new render takes over the listener ids known to the host
+pub(crate) fn diff_elements(old: Option<&UIElement>, new: Option<&mut UIElement>) -> Vec<VDOMChange> {
+    let mut changes = vec![];
+    let mut listeners = ListenerChanges::default();
+    match (old, new) {
+        (Some(old), Some(new)) => {
+            compare_element(&mut vec![0], old, new, &mut changes, &mut listeners);
+            listeners.apply(new);
+        }
+        (None, Some(new)) => changes.push(VDOMChange::ReplaceElement {
+            path: vec![0],
+            element: new.clone(),
+        }),
+        (Some(old), None) => {
+            release_element_listeners(old);
+            changes.push(VDOMChange::RemoveAll);
+        }
+        (None, None) => {}
+    }
+
+    changes
+}
+
+/// Emits only the changed properties of the style. Removed properties are sent as `null`, so the host resets them
+fn compare_style(
+    index: &[i32],
+    old_style: &serde_json::Map<String, Value>,
+    new_style: &serde_json::Map<String, Value>,
+    changes: &mut Vec<VDOMChange>,
+) {
+    let mut styles: HashMap<String, Value> = new_style
+        .iter()
+        .filter(|(key, value)| old_style.get(*key) != Some(*value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    styles.extend(
+        old_style
+            .keys()
+            .filter(|key| !new_style.contains_key(*key))
+            .map(|key| (key.clone(), Value::Null)),
+    );
+
+    if !styles.is_empty() {
+        changes.push(VDOMChange::UpdateStyle {
+            path: index.to_vec(),
+            styles,
+        });
+    }
+}
+
+/// Identity of a child used to match old and new children. Children without a key are matched by their order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ChildKey<'a> {
+    Key(&'a String),
+    Index(usize),
+}
+
+fn child_keys<'a>(children: &'a [FlatChild]) -> Vec<ChildKey<'a>> {
+    let mut unkeyed = 0;
+    children
+        .iter()
+        .map(|child| match child.key() {
+            Some(key) => ChildKey::Key(key),
+            None => {
+                unkeyed += 1;
+                ChildKey::Index(unkeyed - 1)
+            }
+        })
+        .collect()
+}
+
 fn compare_children(
     parent_index: &mut Vec<i32>,
     old_children: &Option<Vec<UIElementChildren>>,
     new_children: &Option<Vec<UIElementChildren>>,
     changes: &mut Vec<VDOMChange>,
+    listeners: &mut ListenerChanges,
 ) {
-    match (old_children, new_children) {
-        (Some(old), Some(new)) => {
-            let max_len = old.len().max(new.len());
-            for i in 0..max_len {
-                parent_index.push(i as i32);
-                match (old.get(i), new.get(i)) {
-                    (
-                        Some(UIElementChildren::Element(old_elem)),
-                        Some(UIElementChildren::Element(new_elem)),
-                    ) => {
-                        compare_element(parent_index, old_elem, new_elem, changes);
-                    }
-                    (Some(_), Some(_)) if old[i] != new[i] => {
-                        changes.push(VDOMChange::UpdateChild {
-                            path: parent_index.clone(),
-                            child: new[i].clone(),
-                        });
-                    }
-                    (None, Some(new_child)) => {
-                        changes.push(VDOMChange::AddElement {
-                            path: parent_index.clone(),
-                            element: UIElement::Native(NativeElement {
-                                native_type: NativeElementType::Fragment,
-                                props: None,
-                                children: Some(vec![new_child.clone()]),
-                                key: None,
-                                events: None,
-                            }),
-                        });
-                    }
-                    (Some(_), None) => {
-                        changes.push(VDOMChange::RemoveElement {
-                            path: parent_index.clone(),
-                        });
-                    }
-                    _ => {}
+    let old = flatten_children(old_children.as_deref().unwrap_or_default());
+    let new = flatten_children(new_children.as_deref().unwrap_or_default());
+
+    match (old.is_empty(), new.is_empty()) {
+        (true, false) => changes.push(VDOMChange::UpdateChildren {
+            path: parent_index.clone(),
+            children: new.iter().map(FlatChild::to_children).collect(),
+        }),
+        (false, true) => {
+            for child in &old {
+                if let FlatChild::Element(element) = child {
+                    listeners.release_element(element);
+                }
+            }
+
+            changes.push(VDOMChange::RemoveChildren {
+                path: parent_index.clone(),
+            })
+        }
+        (false, false) => reconcile_children(parent_index, &old, &new, changes, listeners),
+        (true, true) => {}
+    }
+}
+
+/// Matches the children by their keys and emits removes (in descending order), then moves and adds in ascending order,
+/// so every path is valid at the time the host applies the change
+fn reconcile_children(
+    parent_index: &mut Vec<i32>,
+    old: &[FlatChild],
+    new: &[FlatChild],
+    changes: &mut Vec<VDOMChange>,
+    listeners: &mut ListenerChanges,
+) {
+    let old_keys = child_keys(old);
+    let new_keys = child_keys(new);
+
+    // Only the first child with a duplicate key can be matched, the others are removed or added
+    let mut old_positions: HashMap<&ChildKey, usize> = HashMap::new();
+    for (position, key) in old_keys.iter().enumerate() {
+        old_positions.entry(key).or_insert(position);
+    }
+    let new_key_set: HashSet<&ChildKey> = new_keys.iter().collect();
+
+    let retained: Vec<bool> = old_keys
+        .iter()
+        .enumerate()
+        .map(|(position, key)| old_positions[key] == position && new_key_set.contains(key))
+        .collect();
+    for position in (0..old.len()).rev().filter(|position| !retained[*position]) {
+        if let FlatChild::Element(element) = &old[position] {
+            listeners.release_element(element);
+        }
+
+        parent_index.push(position as i32);
+        changes.push(VDOMChange::RemoveElement {
+            path: parent_index.clone(),
+        });
+        parent_index.pop();
+    }
+
+    // The first new child with a key takes over the old child, the others are added
+    let mut matched: HashSet<usize> = HashSet::new();
+    let new_to_old: Vec<Option<usize>> = new_keys
+        .iter()
+        .map(|key| {
+            old_positions
+                .get(key)
+                .copied()
+                .filter(|position| matched.insert(*position))
+        })
+        .collect();
+
+    // Mirrors the host children by their new index
+    let mut current: Vec<usize> = vec![0; old.len()];
+    for (index, old_position) in new_to_old.iter().enumerate() {
+        if let Some(old_position) = old_position {
+            current[*old_position] = index;
+        }
+    }
+    current = (0..old.len())
+        .filter(|position| retained[*position])
+        .map(|position| current[position])
+        .collect();
+
+    // Children already in the new order stay in place, only the others are moved
+    let stable = longest_increasing_subsequence(&current);
+
+    // Children are placed from the end, each one before the already placed next child
+    for index in (0..new.len()).rev() {
+        let anchor = if index + 1 < new.len() {
+            position_of(&current, index + 1)
+        } else {
+            current.len()
+        };
+
+        match new_to_old[index] {
+            Some(_) if stable.contains(&index) => {}
+            Some(_) => {
+                let position = position_of(&current, index);
+                let to = if position < anchor {
+                    anchor - 1
+                } else {
+                    anchor
+                };
+                if position != to {
+                    parent_index.push(position as i32);
+                    changes.push(VDOMChange::MoveElement {
+                        path: parent_index.clone(),
+                        to: to as i32,
+                    });
+                    parent_index.pop();
+
+                    current.remove(position);
+                    current.insert(to, index);
                 }
+            }
+            None => {
+                parent_index.push(anchor as i32);
+                changes.push(VDOMChange::AddElement {
+                    path: parent_index.clone(),
+                    element: match &new[index] {
+                        FlatChild::Element(element) => (*element).clone(),
+                        child => UIElement::Native(NativeElement {
+                            native_type: NativeElementType::Fragment,
+                            props: None,
+                            children: Some(vec![child.to_children()]),
+                            key: None,
+                            events: None,
+                            content_hash: Default::default(),
+                        }),
+                    },
+                });
                 parent_index.pop();
+
+                current.insert(anchor, index);
             }
         }
-        (None, Some(new)) if !new.is_empty() => {
-            changes.push(VDOMChange::UpdateChildren {
-                path: parent_index.clone(),
-                children: new.clone(),
-            });
+    }
+
+    // The host children are in the new order now, so the retained children are compared at their new index
+    for (index, old_position) in new_to_old.iter().enumerate() {
+        if let Some(old_position) = old_position {
+            parent_index.push(index as i32);
+            compare_child(
+                parent_index,
+                &old[*old_position],
+                &new[index],
+                changes,
+                listeners,
+            );
+            parent_index.pop();
         }
-        (Some(old), None) if !old.is_empty() => {
-            changes.push(VDOMChange::RemoveChildren {
-                path: parent_index.clone(),
+    }
+}
+
+fn position_of(children: &[usize], index: usize) -> usize {
+    children
+        .iter()
+        .position(|child| *child == index)
+        .expect("The placed child should be among the host children")
+}
+
+/// Returns the values of the longest strictly increasing subsequence
+fn longest_increasing_subsequence(values: &[usize]) -> HashSet<usize> {
+    // Position of the smallest tail of every subsequence length, and the predecessor of every position
+    let mut tails: Vec<usize> = vec![];
+    let mut predecessors: Vec<Option<usize>> = vec![None; values.len()];
+
+    for (position, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|tail| values[*tail] < *value);
+        predecessors[position] = length.checked_sub(1).map(|previous| tails[previous]);
+        if length == tails.len() {
+            tails.push(position);
+        } else {
+            tails[length] = position;
+        }
+    }
+
+    let mut subsequence = HashSet::new();
+    let mut position = tails.last().copied();
+    while let Some(current) = position {
+        subsequence.insert(values[current]);
+        position = predecessors[current];
+    }
+
+    subsequence
+}
+
+fn compare_child(
+    index: &mut Vec<i32>,
+    old: &FlatChild,
+    new: &FlatChild,
+    changes: &mut Vec<VDOMChange>,
+    listeners: &mut ListenerChanges,
+) {
+    match (old, new) {
+        (FlatChild::Element(old_elem), FlatChild::Element(new_elem)) => {
+            compare_element(index, old_elem, new_elem, changes, listeners);
+        }
+        (old, new) if old != new => {
+            if let FlatChild::Element(element) = old {
+                listeners.release_element(element);
+            }
+
+            changes.push(VDOMChange::UpdateChild {
+                path: index.clone(),
+                child: new.to_children(),
             });
         }
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::ui::{
+        elements::{ExternalElement, FlatList, TextInput, View},
+        events::bindings::{ENTERED_TEXTS, REGISTERED_EVENTS},
+        list::ListData,
+        style::Style,
+    };
+
+    fn view(key: Option<&str>, children: Vec<UIElement>) -> UIElement {
+        UIElement::Native(NativeElement::new(
+            NativeElementType::View,
+            None,
+            Some(
+                children
+                    .into_iter()
+                    .map(UIElementChildren::Element)
+                    .collect(),
+            ),
+            key.map(String::from),
+        ))
+    }
+
+    fn keyed(keys: &[&str]) -> UIElement {
+        view(
+            None,
+            keys.iter().map(|key| view(Some(key), vec![])).collect(),
+        )
+    }
+
+    fn diff(old: &UIElement, new: &UIElement) -> Vec<VDOMChange> {
+        let mut changes = vec![];
+        compare_element(
+            &mut vec![0],
+            old,
+            new,
+            &mut changes,
+            &mut ListenerChanges::default(),
+        );
+        changes
+    }
+
+    /// Applies the child changes of the root like the host does and returns the keys of the children
+    fn apply(keys: &[&str], changes: &[VDOMChange]) -> Vec<String> {
+        let mut children: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        for change in changes {
+            match change {
+                VDOMChange::RemoveElement { path } => {
+                    children.remove(path[1] as usize);
+                }
+                VDOMChange::AddElement { path, element } => {
+                    let UIElement::Native(native) = element else {
+                        panic!("Expected a native element");
+                    };
+                    children.insert(path[1] as usize, native.key.clone().unwrap());
+                }
+                VDOMChange::MoveElement { path, to } => {
+                    let child = children.remove(path[1] as usize);
+                    children.insert(*to as usize, child);
+                }
+                change => panic!("Unexpected change {:?}", change),
+            }
+        }
+
+        children
+    }
+
+    fn external(props: &[(&str, Value)], children: Vec<UIElement>) -> UIElement {
+        UIElement::External(ExternalElement::new(
+            uuid::Uuid::nil(),
+            Some(
+                props
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.clone()))
+                    .collect(),
+            ),
+            Some(
+                children
+                    .into_iter()
+                    .map(UIElementChildren::Element)
+                    .collect(),
+            ),
+            None,
+        ))
+    }
+
+    fn styled(style: Style) -> UIElement {
+        UIElement::Native(NativeElement::new(
+            NativeElementType::View,
+            View {
+                style: Some(Box::new(style)),
+                ..Default::default()
+            }
+            .into(),
+            None,
+            None,
+        ))
+    }
+
+    fn text_input(value: &str, listener: &str) -> UIElement {
+        let input = UIElement::Native(NativeElement::new(
+            NativeElementType::TextInput,
+            TextInput {
+                value: Some(value.to_string().into()),
+                ..Default::default()
+            }
+            .into(),
+            None,
+            None,
+        ));
+        with_listener(input, CHANGE_TEXT_EVENT, listener)
+    }
+
+    fn enter_text(listener: &str, text: &str) {
+        ENTERED_TEXTS.with(|texts| {
+            texts
+                .borrow_mut()
+                .insert(listener.to_string(), text.to_string())
+        });
+    }
+
+    fn with_listener(mut element: UIElement, name: &str, id: &str) -> UIElement {
+        let events = match &mut element {
+            UIElement::Native(native) => &mut native.events,
+            UIElement::External(external) => &mut external.events,
+        };
+        events
+            .get_or_insert_with(HashMap::new)
+            .entry(name.to_string())
+            .or_default()
+            .insert(id.to_string());
+
+        element
+    }
+
+    fn register(id: &str) {
+        REGISTERED_EVENTS.with(|events| {
+            events
+                .borrow_mut()
+                .insert(id.to_string(), Rc::new(RefCell::new(Box::new(|_| {}))))
+        });
+    }
+
+    fn registered(id: &str) -> bool {
+        REGISTERED_EVENTS.with(|events| events.borrow().contains_key(id))
+    }
+
+    fn listener_ids(element: &UIElement) -> Vec<&String> {
+        element_events(element)
+            .iter()
+            .flat_map(|events| events.values().flatten())
+            .collect()
+    }
+
+    fn moves(changes: &[VDOMChange]) -> usize {
+        changes
+            .iter()
+            .filter(|change| matches!(change, VDOMChange::MoveElement { .. }))
+            .count()
+    }
+
+    #[test]
+    fn moving_the_first_child_to_the_end_is_one_move() {
+        let changes = diff(&keyed(&["a", "b", "c", "d"]), &keyed(&["b", "c", "d", "a"]));
+
+        assert_eq!(moves(&changes), 1);
+        assert_eq!(apply(&["a", "b", "c", "d"], &changes), ["b", "c", "d", "a"]);
+    }
+
+    #[test]
+    fn reversed_children_keep_one_child_in_place() {
+        let changes = diff(&keyed(&["a", "b", "c", "d"]), &keyed(&["d", "c", "b", "a"]));
+
+        assert_eq!(moves(&changes), 3);
+        assert_eq!(apply(&["a", "b", "c", "d"], &changes), ["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn unchanged_order_has_no_changes() {
+        let changes = diff(&keyed(&["a", "b", "c"]), &keyed(&["a", "b", "c"]));
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_children_are_placed_by_key() {
+        let changes = diff(&keyed(&["a", "b", "c"]), &keyed(&["y", "a", "x", "c"]));
+
+        assert_eq!(moves(&changes), 0);
+        assert_eq!(apply(&["a", "b", "c"], &changes), ["y", "a", "x", "c"]);
+    }
+
+    #[test]
+    fn every_permutation_moves_only_the_children_out_of_order() {
+        fn permutations(keys: Vec<&'static str>) -> Vec<Vec<&'static str>> {
+            if keys.len() <= 1 {
+                return vec![keys];
+            }
+
+            (0..keys.len())
+                .flat_map(|index| {
+                    let mut rest = keys.clone();
+                    let first = rest.remove(index);
+                    permutations(rest).into_iter().map(move |mut permutation| {
+                        permutation.insert(0, first);
+                        permutation
+                    })
+                })
+                .collect()
+        }
+
+        let old = ["a", "b", "c", "d", "e"];
+        for new in permutations(old.to_vec()) {
+            let changes = diff(&keyed(&old), &keyed(&new));
+            let positions: Vec<usize> = new
+                .iter()
+                .map(|key| old.iter().position(|old| old == key).unwrap())
+                .collect();
+
+            assert_eq!(apply(&old, &changes), new);
+            assert_eq!(
+                moves(&changes),
+                new.len() - longest_increasing_subsequence(&positions).len(),
+                "{:?}",
+                new
+            );
+        }
+    }
+
+    #[test]
+    fn unchanged_subtrees_have_no_changes() {
+        let tree = || {
+            view(
+                None,
+                vec![external(
+                    &[("title", "Hello".into()), ("count", 1.into())],
+                    vec![view(Some("a"), vec![])],
+                )],
+            )
+        };
+
+        assert_eq!(tree().content_hash(), tree().content_hash());
+        assert!(diff(&tree(), &tree()).is_empty());
+    }
+
+    #[test]
+    fn listener_ids_do_not_change_the_hash() {
+        let old = with_listener(view(None, vec![]), "onPress", "old");
+        let new = with_listener(view(None, vec![]), "onPress", "new");
+
+        assert_eq!(old.content_hash(), new.content_hash());
+        assert_ne!(
+            old.content_hash(),
+            with_listener(view(None, vec![]), "onLongPress", "new").content_hash()
+        );
+    }
+
+    #[test]
+    fn changed_props_deep_in_the_tree_are_updated() {
+        let old = view(
+            None,
+            vec![view(
+                None,
+                vec![external(&[("title", "Hello".into())], vec![])],
+            )],
+        );
+        let new = view(
+            None,
+            vec![view(
+                None,
+                vec![external(&[("title", "World".into())], vec![])],
+            )],
+        );
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 1);
+        let VDOMChange::UpdateProp { path, key, value } = &changes[0] else {
+            panic!("Expected an UpdateProp, got {:?}", changes[0]);
+        };
+        assert_eq!(path, &[0, 0, 0]);
+        assert_eq!(key, "title");
+        assert_eq!(value, "World");
+    }
+
+    #[test]
+    fn removed_props_are_removed() {
+        let old = external(&[("title", "Hello".into()), ("count", 1.into())], vec![]);
+        let new = external(&[("title", "Hello".into())], vec![]);
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert!(
+            matches!(&changes[0], VDOMChange::RemoveProp { path, key } if path == &[0] && key == "count"),
+            "{:?}",
+            changes
+        );
+    }
+
+    #[test]
+    fn new_listeners_take_over_the_old_ids() {
+        let old = with_listener(view(None, vec![]), "onPress", "old-press");
+        let mut new = with_listener(view(None, vec![]), "onPress", "new-press");
+        register("old-press");
+        register("new-press");
+
+        let changes = diff_elements(Some(&old), Some(&mut new));
+
+        assert!(changes.is_empty(), "{:?}", changes);
+        assert_eq!(listener_ids(&new), ["old-press"]);
+        assert!(registered("old-press"));
+        assert!(!registered("new-press"));
+    }
+
+    #[test]
+    fn removed_listeners_are_released() {
+        let old = with_listener(
+            with_listener(view(None, vec![]), "onPress", "kept-press"),
+            "onLongPress",
+            "removed-long-press",
+        );
+        let mut new = with_listener(view(None, vec![]), "onPress", "new-kept-press");
+        register("kept-press");
+        register("removed-long-press");
+        register("new-kept-press");
+
+        let changes = diff_elements(Some(&old), Some(&mut new));
+
+        assert_eq!(changes.len(), 1);
+        assert!(
+            matches!(&changes[0], VDOMChange::RemoveEventListener { name, id, .. } if name == "onLongPress" && id == "removed-long-press"),
+            "{:?}",
+            changes
+        );
+        assert_eq!(listener_ids(&new), ["kept-press"]);
+        assert!(registered("kept-press"));
+        assert!(!registered("removed-long-press"));
+    }
+
+    #[test]
+    fn replaced_elements_release_the_listeners_of_their_subtree() {
+        let pressable = with_listener(view(None, vec![]), "onPress", "replaced-press");
+        let old = view(None, vec![view(None, vec![pressable])]);
+        let mut new = view(None, vec![external(&[], vec![])]);
+        register("replaced-press");
+
+        let changes = diff_elements(Some(&old), Some(&mut new));
+
+        assert!(
+            matches!(changes.as_slice(), [VDOMChange::ReplaceElement { path, .. }] if path == &[0, 0]),
+            "{:?}",
+            changes
+        );
+        assert!(!registered("replaced-press"));
+    }
+
+    #[test]
+    fn changed_styles_send_only_the_changed_properties() {
+        let old = styled(Style {
+            flex: Some(1.0),
+            opacity: Some(0.5),
+            ..Default::default()
+        });
+        let new = styled(Style {
+            flex: Some(1.0),
+            opacity: Some(1.0),
+            ..Default::default()
+        });
+
+        let changes = diff(&old, &new);
+        let [VDOMChange::UpdateStyle { path, styles }] = changes.as_slice() else {
+            panic!("Expected a single UpdateStyle, got {:?}", changes);
+        };
+        assert_eq!(path, &[0]);
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles["opacity"], 1.0);
+    }
+
+    #[test]
+    fn removed_style_properties_are_sent_as_null() {
+        let old = styled(Style {
+            flex: Some(1.0),
+            opacity: Some(0.5),
+            ..Default::default()
+        });
+        let new = styled(Style {
+            flex: Some(1.0),
+            ..Default::default()
+        });
+
+        let changes = diff(&old, &new);
+        let [VDOMChange::UpdateStyle { styles, .. }] = changes.as_slice() else {
+            panic!("Expected a single UpdateStyle, got {:?}", changes);
+        };
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles["opacity"], Value::Null);
+    }
+
+    #[test]
+    fn added_and_removed_styles_replace_the_style_prop() {
+        let view = |style: Option<Style>| {
+            UIElement::Native(NativeElement::new(
+                NativeElementType::View,
+                View {
+                    class_name: Some("p-2".into()),
+                    style: style.map(Box::new),
+                    ..Default::default()
+                }
+                .into(),
+                None,
+                None,
+            ))
+        };
+        let unstyled = view(None);
+        let style = view(Some(Style {
+            opacity: Some(0.5),
+            ..Default::default()
+        }));
+
+        let added = diff(&unstyled, &style);
+        assert!(
+            matches!(added.as_slice(), [VDOMChange::UpdateProp { key, .. }] if key == STYLE_PROP),
+            "{:?}",
+            added
+        );
+        let removed = diff(&style, &unstyled);
+        assert!(
+            matches!(removed.as_slice(), [VDOMChange::RemoveProp { key, .. }] if key == STYLE_PROP),
+            "{:?}",
+            removed
+        );
+    }
+
+    #[test]
+    fn rejected_entered_text_sends_the_value_again() {
+        let old_input = text_input("Ada", "rejected");
+        let new_input = text_input("Ada", "new-rejected");
+        let old = view(None, vec![view(None, vec![]), old_input]);
+        let new = view(None, vec![view(None, vec![]), new_input]);
+        enter_text("rejected", "Adaa");
+
+        let changes = diff(&old, &new);
+
+        assert!(
+            matches!(changes.as_slice(), [VDOMChange::UpdateProp { path, key, value }]
+                if path == &[0, 1] && key == VALUE_PROP && value == "Ada"),
+            "{:?}",
+            changes
+        );
+    }
+
+    #[test]
+    fn accepted_entered_text_has_no_changes() {
+        let old = text_input("Ada", "accepted");
+        let new = text_input("Ada", "new-accepted");
+        enter_text("accepted", "Ada");
+
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn changed_value_is_sent_once() {
+        let old = text_input("Ada", "changed");
+        let new = text_input("ADA", "new-changed");
+        enter_text("changed", "ada");
+
+        let changes = diff(&old, &new);
+
+        assert!(
+            matches!(changes.as_slice(), [VDOMChange::UpdateProp { value, .. }] if value == "ADA"),
+            "{:?}",
+            changes
+        );
+    }
+
+    #[test]
+    fn list_path_ends_at_the_list_element() {
+        let list = UIElement::Native(NativeElement::new(
+            NativeElementType::FlatList,
+            FlatList {
+                data: Some(ListData {
+                    id: "feed".to_string(),
+                    keys: vec![],
+                }),
+                ..Default::default()
+            }
+            .into(),
+            None,
+            None,
+        ));
+        let list = with_listener(list, "onPress", "list-press");
+        let page = view(None, vec![view(None, vec![]), list]);
+        let root = with_listener(page, "onPress", "page");
+
+        let path = list_path(&root, "feed").expect("list is rendered");
+
+        assert_eq!(path.len(), 2);
+        assert!(path[0]["onPress"].contains("page"));
+        assert!(path[1]["onPress"].contains("list-press"));
+        assert!(list_path(&root, "other").is_none());
+    }
+}
@@ -1,36 +1,249 @@
-use diffs::{compare_element, VDOMChange};
+use std::{cell::RefCell, rc::Rc};
+
+use diffs::{compare_element, ListenerChanges, VDOMChange};
+pub(crate) use diffs::{
+    diff_elements, list_path, listener_path, release_element_listeners, share_listeners,
+    ElementListeners,
+};
 use uuid::Uuid;
 
-use crate::{info, logging};
+use crate::logging::{self, LogLevel, LogRecord};
 
-use super::{components::AnyComponent, elements::UIElement};
+use super::{
+    components::{
+        mount_children, propagate_lifecycle, AnyComponent, AppState, LifecycleEvent, RenderError,
+    },
+    elements::UIElement,
+    events::bindings::{has_entered_texts_for, with_listener_owner, ListenerOwner},
+    hooks::{self, HookStore},
+    list,
+};
 
 mod bindings;
 
 mod diffs;
-use bindings::process_component_render_changes;
+use bindings::{on_component_error, process_component_render_changes};
+
+pub mod scheduler;
+pub(crate) use scheduler::mark_dirty;
+
+/// Target of the records logged when a component fails to render
+pub const RENDER_TARGET: &str = "render";
+
+/// Identifies one instance of a registered component, e.g. a profile badge shown on every post of a feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstanceId {
+    pub component_id: Uuid,
+    pub instance: u32,
+}
+impl std::fmt::Display for InstanceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.component_id, self.instance)
+    }
+}
 
 pub struct Renderer {
-    id: Uuid,
+    id: InstanceId,
     root: Box<dyn AnyComponent>,
     vdom: Option<UIElement>,
+    last_props: Option<String>,
+    error: Option<RenderError>,
+    hooks: Rc<RefCell<HookStore>>,
+    mounted: bool,
+    visible: bool,
 }
 
 impl Renderer {
-    pub fn new(id: Uuid, root_component: Box<dyn AnyComponent>) -> Renderer {
+    pub fn new(id: InstanceId, root_component: Box<dyn AnyComponent>) -> Renderer {
         Renderer {
             root: root_component,
             vdom: None,
+            last_props: None,
+            error: None,
+            hooks: Rc::default(),
+            mounted: false,
+            visible: true,
             id,
         }
     }
 
+    /// Returns the error of the last render, while the fallback is shown
+    pub fn error(&self) -> Option<&RenderError> {
+        self.error.as_ref()
+    }
+
+    /// Renders the component again with the last props, e.g. after the user pressed retry in the fallback
+    pub fn retry(&mut self) -> Option<String> {
+        self.render(self.last_props.clone())
+    }
+
+    /// Renders the component again with the last props and sends the changes to the host.
+    /// Does nothing if the host has not received the rendered UI yet
+    pub fn rerender(&mut self) {
+        if self.vdom.is_none() {
+            return;
+        }
+
+        self.render(self.last_props.clone());
+    }
+
+    pub fn id(&self) -> InstanceId {
+        self.id
+    }
+
+    pub fn is_mounted(&self) -> bool {
+        self.mounted
+    }
+
+    /// Invoked once the host shows the rendered component. The nested components are mounted before the root
+    pub fn mount(&mut self) {
+        if self.mounted {
+            return;
+        }
+
+        self.mounted = true;
+        mount_children(&self.hooks);
+        let root = &mut self.root;
+        logging::with_component_scope(self.id.to_string(), || root.mount());
+    }
+
+    /// Invoked once the host removes the component. The state and the nested components are dropped,
+    /// so the next render starts from scratch and returns the whole UI
+    pub fn unmount(&mut self) {
+        if !self.mounted {
+            return;
+        }
+
+        self.mounted = false;
+        let root = &mut self.root;
+        logging::with_component_scope(self.id.to_string(), || {
+            root.unmount();
+
+            // Nested components are unmounted once their instances are dropped
+            drop(std::mem::take(&mut self.hooks));
+        });
+
+        if let Some(vdom) = self.vdom.take() {
+            release_element_listeners(&vdom);
+        }
+        scheduler::unmark_dirty(&self.id);
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        if self.visible == visible {
+            return;
+        }
+
+        self.visible = visible;
+        self.notify_lifecycle(LifecycleEvent::Visibility(visible));
+    }
+
+    pub fn app_state_changed(&mut self, state: AppState) {
+        self.notify_lifecycle(LifecycleEvent::AppState(state));
+    }
+
+    fn notify_lifecycle(&mut self, event: LifecycleEvent) {
+        let root = &mut self.root;
+        let hooks = &self.hooks;
+        logging::with_component_scope(self.id.to_string(), || {
+            match event {
+                LifecycleEvent::Visibility(visible) => root.visibility_changed(visible),
+                LifecycleEvent::AppState(state) => root.app_state_changed(state),
+            }
+            propagate_lifecycle(hooks, event);
+        });
+    }
+
+    /// Renders the root component. Render errors are caught, so only this component shows its fallback
+    fn render_root(&mut self, props: Option<String>) -> Option<UIElement> {
+        let root = &mut self.root;
+        let result = with_listener_owner(ListenerOwner::Instance(self.id), || {
+            logging::with_component_scope(self.id.to_string(), || {
+                hooks::with_hooks(self.id, self.hooks.clone(), || root.render(props))
+            })
+        });
+
+        match result {
+            Ok(element) => {
+                self.error = None;
+                Some(element)
+            }
+            Err(error) => {
+                self.report_error(&error);
+                let root = &self.root;
+                let fallback =
+                    with_listener_owner(ListenerOwner::Instance(self.id), || root.fallback(&error));
+                self.error = Some(error);
+
+                fallback
+            }
+        }
+    }
+
+    /// Returns whether the rendered UI can be kept, as neither the props, the state nor the used contexts changed.
+    /// Texts entered into its inputs are checked by the diff, so they are never skipped
+    fn can_skip_render(&self, props: &Option<String>) -> bool {
+        if self.vdom.is_none()
+            || self.error.is_some()
+            || self.hooks.borrow().needs_render()
+            || has_entered_texts_for(self.id)
+        {
+            return false;
+        }
+
+        let root = &self.root;
+        logging::with_component_scope(self.id.to_string(), || !root.should_render(props))
+    }
+
+    fn report_error(&self, error: &RenderError) {
+        let mut record = LogRecord::new(
+            LogLevel::Error,
+            RENDER_TARGET,
+            format!("Failed to render component {} -> {}", self.id, error),
+        );
+        record.component_id = Some(self.id.to_string());
+        logging::report_error(record, None, false);
+
+        match serde_json::to_string(error) {
+            Ok(error) => on_component_error(
+                self.id.component_id.to_string(),
+                error,
+                self.id.instance,
+            ),
+            Err(e) => logging::adv_error(
+                format!("Failed to serialize the render error: {}", e).as_str(),
+                None,
+                false,
+            ),
+        }
+    }
+
+    /// Returns the listeners of the elements from the root to the element holding the listener id
+    pub(crate) fn listener_path(&self, id: &str) -> Option<Vec<ElementListeners>> {
+        self.vdom.as_ref().and_then(|vdom| listener_path(vdom, id))
+    }
+
+    /// Returns the listeners of the elements from the root to the list element showing the items of the list
+    pub(crate) fn list_path(&self, list_id: &str) -> Option<Vec<ElementListeners>> {
+        self.vdom.as_ref().and_then(|vdom| list_path(vdom, list_id))
+    }
+
     pub fn render(&mut self, props: Option<String>) -> Option<String> {
-        let result_element = self.root.render(props);
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("render", component_id = %self.id).entered();
+
+        scheduler::unmark_dirty(&self.id);
+        if self.can_skip_render(&props) {
+            self.last_props = props;
+            return None;
+        }
+
+        self.last_props = props.clone();
+        let mut result_element = self.render_root(props);
 
         // If diff returns true, we should return the rendered UI
-        if self.diff(&result_element) {
-            let result = match serde_json::to_string(&result_element) {
+        let result = if self.diff(&mut result_element) {
+            match serde_json::to_string(&result_element) {
                 Ok(res) => Some(res),
                 Err(e) => {
                     logging::adv_error(
@@ -41,18 +254,23 @@ impl Renderer {
 
                     None
                 }
-            };
-
-            self.vdom = result_element;
-            return result;
+            }
         } else {
-            self.vdom = result_element;
+            None
+        };
+        self.vdom = result_element;
+
+        // Nested components added by the render of a mounted component are shown once the changes are applied
+        if self.mounted {
+            mount_children(&self.hooks);
         }
 
-        None
+        // Items of the lists are rendered outside of the component render
+        list::render_stale_lists();
+        result
     }
 
-    fn diff(&self, new_vdom_raw: &Option<UIElement>) -> bool {
+    fn diff(&self, new_vdom_raw: &mut Option<UIElement>) -> bool {
         let old_vdom = match &self.vdom {
             Some(vdom) => vdom,
             None => {
@@ -60,12 +278,17 @@ impl Renderer {
             }
         };
 
+        let mut listeners = ListenerChanges::default();
         let new_vdom = match new_vdom_raw {
             Some(vdom) => vdom,
             None => {
+                listeners.release_element(old_vdom);
+                listeners.apply_releases();
+
                 process_component_render_changes(
-                    self.id.to_string(),
-                    serde_json::json!(VDOMChange::RemoveAll).to_string(),
+                    self.id.component_id.to_string(),
+                    serde_json::json!([VDOMChange::RemoveAll]).to_string(),
+                    self.id.instance,
                 );
                 return false;
             }
@@ -74,15 +297,35 @@ impl Renderer {
         let mut element_index = vec![0];
         let mut changes: Vec<VDOMChange> = Vec::new();
 
-        compare_element(&mut element_index, old_vdom, new_vdom, &mut changes);
+        compare_element(
+            &mut element_index,
+            old_vdom,
+            new_vdom,
+            &mut changes,
+            &mut listeners,
+        );
+        listeners.apply(new_vdom);
 
         if changes.len() > 0 {
             let changes_json = serde_json::json!(changes).to_string();
-            info!("Changes {}", changes_json);
-
-            process_component_render_changes(self.id.to_string(), changes_json);
+            process_component_render_changes(
+                self.id.component_id.to_string(),
+                changes_json,
+                self.id.instance,
+            );
         }
 
         false
     }
 }
+
+impl Drop for Renderer {
+    /// Unmounts the component and frees the listeners of the rendered tree, e.g. when the component is removed
+    fn drop(&mut self) {
+        self.unmount();
+
+        if let Some(vdom) = &self.vdom {
+            release_element_listeners(vdom);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{bindings::request_frame, InstanceId, RENDER_TARGET};
+use crate::{ui::events::bindings::clear_entered_texts, warn};
+
+/// Number of consecutive frames a component may mark itself dirty while rendering before it is reported as a render loop
+const MAX_SELF_DIRTY_FRAMES: usize = 32;
+
+thread_local! {
+    /// Components whose state changed since the last frame, in the order they were marked
+    static DIRTY_COMPONENTS: RefCell<Vec<InstanceId>> = const { RefCell::new(Vec::new()) };
+    static FRAME_REQUESTED: Cell<bool> = const { Cell::new(false) };
+    /// Number of consecutive frames the component was marked dirty again by its own render
+    static SELF_DIRTY_FRAMES: RefCell<HashMap<InstanceId, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Schedules a render of the component on the next host frame. All changes made until then are rendered at once
+pub(crate) fn mark_dirty(id: InstanceId) {
+    DIRTY_COMPONENTS.with_borrow_mut(|dirty| {
+        if !dirty.contains(&id) {
+            dirty.push(id);
+        }
+    });
+
+    if !FRAME_REQUESTED.replace(true) {
+        request_frame();
+    }
+}
+
+/// Removes the component from the next frame, e.g. because the host has just rendered it
+pub(crate) fn unmark_dirty(id: &InstanceId) {
+    DIRTY_COMPONENTS.with_borrow_mut(|dirty| dirty.retain(|dirty_id| dirty_id != id));
+}
+
+/// Returns whether a component instance is waiting for the next frame to be rendered
+pub fn is_dirty(id: &InstanceId) -> bool {
+    DIRTY_COMPONENTS.with_borrow(|dirty| dirty.contains(id))
+}
+
+/// Renders every dirty component once and sends one change set per component to the host.
+/// Components marked as dirty while rendering are rendered on the following frame
+pub fn render_dirty_components() {
+    let mut self_dirty = SELF_DIRTY_FRAMES.take();
+    let mut still_dirty = HashMap::new();
+    for id in DIRTY_COMPONENTS.take() {
+        crate::ui::bindings::rerender_component(&id);
+        if !is_dirty(&id) {
+            continue;
+        }
+
+        let frames = self_dirty.remove(&id).unwrap_or_default() + 1;
+        if frames == MAX_SELF_DIRTY_FRAMES {
+            warn!(
+                target: RENDER_TARGET,
+                "Component {} changed its state while rendering on {} frames in a row. Does it set its state unconditionally during render?",
+                id,
+                frames
+            );
+        }
+        still_dirty.insert(id, frames);
+    }
+
+    SELF_DIRTY_FRAMES.set(still_dirty);
+    clear_entered_texts();
+}
+
+/// Invoked by the host on the frame requested by the scheduler
+#[wasm_bindgen]
+pub fn on_frame(_timestamp: f64) {
+    FRAME_REQUESTED.set(false);
+    render_dirty_components();
+}
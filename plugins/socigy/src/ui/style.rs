@@ -0,0 +1,360 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use socigy_macros::ui_component;
+
+use super::elements::PropStr;
+
+/// Length of a style property. Serialized as a number of points, `"50%"` or `"auto"`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    Points(f64),
+    Percent(f64),
+    Auto,
+}
+impl Serialize for Dimension {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Dimension::Points(points) => serializer.serialize_f64(*points),
+            Dimension::Percent(percent) => serializer.serialize_str(&format!("{}%", percent)),
+            Dimension::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Dimension {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Points(f64),
+            String(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Points(points) => Ok(Dimension::Points(points)),
+            Raw::String(value) if value == "auto" => Ok(Dimension::Auto),
+            Raw::String(value) => value
+                .strip_suffix('%')
+                .and_then(|percent| percent.parse().ok())
+                .map(Dimension::Percent)
+                .ok_or_else(|| serde::de::Error::custom(format!("Invalid dimension '{}'", value))),
+        }
+    }
+}
+impl From<f64> for Dimension {
+    fn from(value: f64) -> Self {
+        Dimension::Points(value)
+    }
+}
+impl From<i32> for Dimension {
+    fn from(value: i32) -> Self {
+        Dimension::Points(value as f64)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FlexWrap {
+    Wrap,
+    NoWrap,
+    WrapReverse,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JustifyContent {
+    FlexStart,
+    FlexEnd,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Alignment used by `align_items`, `align_self` and `align_content`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Align {
+    Auto,
+    FlexStart,
+    FlexEnd,
+    Center,
+    Stretch,
+    Baseline,
+    SpaceBetween,
+    SpaceAround,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Display {
+    Flex,
+    None,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Position {
+    Relative,
+    Absolute,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Overflow {
+    Visible,
+    Hidden,
+    Scroll,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BorderStyle {
+    Solid,
+    Dotted,
+    Dashed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FontWeight {
+    #[serde(rename = "100")]
+    Thin,
+    #[serde(rename = "200")]
+    ExtraLight,
+    #[serde(rename = "300")]
+    Light,
+    #[serde(rename = "400")]
+    Regular,
+    #[serde(rename = "500")]
+    Medium,
+    #[serde(rename = "600")]
+    SemiBold,
+    #[serde(rename = "700")]
+    Bold,
+    #[serde(rename = "800")]
+    ExtraBold,
+    #[serde(rename = "900")]
+    Black,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FontStyle {
+    Normal,
+    Italic,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TextAlign {
+    Auto,
+    Left,
+    Right,
+    Center,
+    Justify,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextDecorationLine {
+    None,
+    Underline,
+    LineThrough,
+    #[serde(rename = "underline line-through")]
+    UnderlineLineThrough,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TextTransform {
+    None,
+    Uppercase,
+    Lowercase,
+    Capitalize,
+}
+
+/// Single transformation of the `transform` style. Angles are strings with a unit, e.g. `"45deg"`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Transform {
+    TranslateX(f64),
+    TranslateY(f64),
+    Scale(f64),
+    ScaleX(f64),
+    ScaleY(f64),
+    Rotate(PropStr),
+    RotateX(PropStr),
+    RotateY(PropStr),
+    RotateZ(PropStr),
+    SkewX(PropStr),
+    SkewY(PropStr),
+}
+
+/// Inline style of a native element, serialized the same way as React Native styles.
+///
+/// Built in `ui!` with the object syntax, e.g. `style={{ flex: 1, padding: 8, background_color: "#fff" }}`,
+/// or with the [`StyleBuilder`]
+#[derive(Clone, Debug, Default)]
+#[ui_component]
+pub struct Style {
+    // Flexbox
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<Display>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex_direction: Option<FlexDirection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex_wrap: Option<FlexWrap>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex_grow: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex_shrink: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flex_basis: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justify_content: Option<JustifyContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align_items: Option<Align>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align_self: Option<Align>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align_content: Option<Align>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_gap: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_gap: Option<f64>,
+
+    // Layout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_index: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<Overflow>,
+
+    // Spacing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_horizontal: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_vertical: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_top: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_right: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_bottom: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin_left: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_horizontal: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_vertical: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_top: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_right: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_bottom: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_left: Option<Dimension>,
+
+    // Colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+
+    // Borders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_top_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_right_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_bottom_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_left_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_style: Option<BorderStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_radius: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_top_left_radius: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_top_right_radius: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_bottom_left_radius: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_bottom_right_radius: Option<f64>,
+
+    // Typography
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<PropStr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_weight: Option<FontWeight>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_style: Option<FontStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letter_spacing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_align: Option<TextAlign>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_decoration_line: Option<TextDecorationLine>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_transform: Option<TextTransform>,
+
+    // Transforms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Vec<Transform>>,
+}
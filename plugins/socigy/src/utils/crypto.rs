@@ -1,12 +1,23 @@
 use uuid::Uuid;
+#[cfg(not(test))]
 use wasm_bindgen::prelude::wasm_bindgen;
 
+#[cfg(not(test))]
 #[wasm_bindgen(js_namespace = ["socigy", "utils", "crypto"])]
 extern "C" {
     #[wasm_bindgen(js_name = "randomV4Uuid")]
     fn internal_random_v4_uuid() -> String;
 }
 
+/// Native tests run without the host, so they get sequential ids
+#[cfg(test)]
+fn internal_random_v4_uuid() -> String {
+    thread_local! {
+        static NEXT_ID: std::cell::Cell<u128> = const { std::cell::Cell::new(1) };
+    }
+    Uuid::from_u128(NEXT_ID.replace(NEXT_ID.get() + 1)).to_string()
+}
+
 pub fn random_v4_uuid() -> Uuid {
     Uuid::parse_str(internal_random_v4_uuid().as_str())
         .expect("The internal function should return valid UUID")
@@ -5,15 +5,6 @@ macro_rules! date_now {
     };
 }
 
-#[macro_export]
-macro_rules! date_now_format_time {
-    () => {
-        crate::format_date_time!(js_sys::Date::new(&wasm_bindgen::JsValue::from(
-            js_sys::Date::now()
-        )))
-    };
-}
-
 #[macro_export]
 macro_rules! format_date_time {
     ($date:expr) => {